@@ -1,3 +1,6 @@
 pub mod services;
 pub mod utilities;
 pub mod telegram_infrastructure;
+
+pub use services::torob_client::TorobClient;
+pub use services::torob_error::TorobError;
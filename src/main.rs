@@ -1,13 +1,99 @@
+use telegram_bot_torob::telegram_infrastructure::dialogue_storage::open_dialogue_storage;
 use telegram_bot_torob::telegram_infrastructure::telegram_bot::TelegramBot;
+use telegram_bot_torob::utilities::config::{init_config, AppConfig, StorageBackend};
+use telegram_bot_torob::utilities::credential_store::{
+    init_credential_store, CredentialStore, EncryptedCredentialStore, MemoryCredentialStore,
+    SqliteCredentialStore,
+};
+use telegram_bot_torob::utilities::crypto::generate_key;
+use telegram_bot_torob::utilities::database::{init_database, open_database, SharedConnection};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let bot_token: String = String::from("8358109688:AAE0QOX-s23RxHAr6cpJSWEb76TREPpoF8c");
+    // زیر‌دستورها: `generate-key` و `reencrypt-tokens`؛ بدون آرگومان ربات اجرا می‌شود
+    let command: Option<String> = std::env::args().nth(1);
+
+    if command.as_deref() == Some("generate-key") {
+        println!("{}", generate_key());
+        return Ok(());
+    }
+    if let Some(other) = command.as_deref()
+        && other != "reencrypt-tokens"
+    {
+        eprintln!("unknown command {other:?}; available: generate-key, reencrypt-tokens");
+        std::process::exit(2);
+    }
+
+    // تنظیمات قبل از ساخت ربات خوانده و اعتبارسنجی می‌شوند
+    let config: &AppConfig = match AppConfig::load() {
+        Ok(cfg) => init_config(cfg),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+
+    env_logger::Builder::new()
+        .parse_filters(&config.log_level)
+        .init();
+
+    // یک اتصال SQLite مشترک برای اعتبارنامه‌ها و وضعیت مکالمه‌ها
+    let database: Option<SharedConnection> = if config.uses_database() {
+        match open_database(&config.storage.database_path) {
+            Ok(conn) => Some(init_database(conn).clone()),
+            Err(e) => {
+                eprintln!(
+                    "cannot open database {}: {e}",
+                    config.storage.database_path.display()
+                );
+                std::process::exit(2);
+            }
+        }
+    } else {
+        None
+    };
+
+    // اطلاعات ورود کاربران باید قبل از شروع ربات در دسترس باشد
+    let raw_store: Box<dyn CredentialStore> = match (config.storage.backend, &database) {
+        (StorageBackend::Sqlite, Some(conn)) => Box::new(SqliteCredentialStore::new(conn.clone())),
+        _ => Box::new(MemoryCredentialStore::new()),
+    };
+
+    // اگر توکنی با کلید ناشناخته رمز شده باشد، ربات شروع نمی‌شود
+    let store = match EncryptedCredentialStore::open(raw_store, config.token_cipher()) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("refusing to start: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    if command.as_deref() == Some("reencrypt-tokens") {
+        if config.storage.encryption_key.is_none() {
+            eprintln!("reencrypt-tokens needs storage.encryption_key (or TOROB_ENCRYPTION_KEY)");
+            std::process::exit(2);
+        }
+        let count = store.reencrypt_all()?;
+        println!("re-encrypted {count} token(s) with the current key");
+        return Ok(());
+    }
+
+    init_credential_store(Box::new(store));
+
+    let dialogue_storage = match open_dialogue_storage(config, database).await {
+        Ok(storage) => storage,
+        Err(e) => {
+            eprintln!("cannot open dialogue storage: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    let bot_token: String = config.bot_token.clone();
 
-    let bot_token_clone: String = bot_token.clone();
     let bot_handle: tokio::task::JoinHandle<()> = tokio::spawn(async move {
         let bot: TelegramBot =
-            telegram_bot_torob::telegram_infrastructure::telegram_bot::TelegramBot::new(bot_token_clone);
+            telegram_bot_torob::telegram_infrastructure::telegram_bot::TelegramBot::new(bot_token)
+                .with_dialogue_storage(dialogue_storage);
         if let Err(e) = bot.run_dispatcher().await {
             eprintln!("bot failed: {e}");
         }
@@ -20,4 +106,4 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _ = bot_handle.await;
 
     Ok(())
-}
\ No newline at end of file
+}
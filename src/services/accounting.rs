@@ -1,3 +1,5 @@
+use crate::services::category_cache::category_cache;
+use crate::utilities::config::http_client_builder;
 use crate::utilities::session::set_session_by_chat;
 // use crate::utilities::session::{set_session_by_chat, set_session_multi, ChatId};
 use crate::utilities::token::set_token;
@@ -5,17 +7,17 @@ use crate::utilities::token::set_token;
 /// روند لاگین در ترب و ذخیره سشن های مربوط به کار با سرویس های ترب
 pub async fn login_in_torob(
     chat_id: &str,
-    user_name: &String,
-    password: &String,
+    user_name: &str,
+    password: &str,
     origin: &str
 ) -> &'static str {
     use reqwest::header::{ORIGIN, REFERER, USER_AGENT};
 
-    let base = reqwest::Url::parse(&origin).unwrap();
+    let base = reqwest::Url::parse(origin).unwrap();
     let url = format!("{}/admin/login/?next=/admin/", &origin);
 
     let jar = std::sync::Arc::new(reqwest::cookie::Jar::default());
-    let client = match reqwest::Client::builder()
+    let client = match http_client_builder()
         .cookie_provider(jar.clone())
         .build()
     {
@@ -23,30 +25,9 @@ pub async fn login_in_torob(
         Err(_) => return "client_build_error",
     };
 
-    // GET اولیه
-    let get_resp = match client.get(&url).header(USER_AGENT, "reqwest").send().await {
-        Ok(r) => r,
-        Err(_) => return "pre_get_error",
-    };
-    
-    let csrf_to_send;
-    
-    // اگر ست‌کوکی csrftoken داد، از همان استفاده کن؛ وگرنه از ورودی
-    for val in get_resp
-        .headers()
-        .get_all(reqwest::header::SET_COOKIE)
-        .iter()
-    {
-        if let Ok(s) = val.to_str() {
-            if let Some(pos) = s.find("csrftoken=") {
-                let rest = &s[pos + "csrftoken=".len()..];
-                let token = rest.split(';').next().unwrap_or("").to_string();
-                if token.is_empty() == false {
-                    csrf_to_send = token;
-                    break;
-                }
-            }
-        }
+    // GET اولیه؛ کوکی csrftoken در jar ذخیره و همراه POST ارسال می‌شود
+    if client.get(&url).header(USER_AGENT, "reqwest").send().await.is_err() {
+        return "pre_get_error";
     }
 
     // POST لاگین
@@ -56,8 +37,8 @@ pub async fn login_in_torob(
         .header(ORIGIN, origin)
         .header(USER_AGENT, "reqwest")
         .form(&[
-            ("username", user_name.as_str()),
-            ("password", password.as_str()),
+            ("username", user_name),
+            ("password", password),
             ("next", "/admin/"),
         ])
         .send()
@@ -68,7 +49,7 @@ pub async fn login_in_torob(
         Err(_) => return "request_error",
     };
 
-    if (r.status().is_success() || r.status().is_redirection()) == false {
+    if !(r.status().is_success() || r.status().is_redirection()) {
         return "http_error";
     }
 
@@ -89,7 +70,7 @@ pub async fn login_in_torob(
 /// or confirmation, as it assumes the operation is always successful.
 ///
 /// # Examples
-/// ```
+/// ```ignore
 /// save_token("12345", "example_token").await;
 /// ```
 ///
@@ -103,4 +84,5 @@ pub async fn login_in_torob(
 /// - Consider implementing logging or success confirmation for the save operation.
 pub async fn save_token(chat_id: &str, token: &str) {
     set_token(chat_id, token);
+    category_cache().invalidate(chat_id);
 }
@@ -0,0 +1,180 @@
+use crate::services::category_service::fetch_categories_from_service;
+use crate::services::models::category::Category;
+use crate::services::torob_error::TorobResult;
+use crate::utilities::config::config;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// ====== کش دسته‌بندی‌های هر چت ======
+///
+/// تا `categories.cache_ttl_secs` تازه است؛ بعد از آن مقدار قدیمی فوراً برگردانده می‌شود
+/// و در پس‌زمینه دوباره خوانده می‌شود (stale-while-revalidate).
+/// جایی که آدرس پنل یا توکن عوض می‌شود (ورود آدرس/توکن، /changetoken) باید `invalidate` صدا زده شود.
+pub struct CategoryCache {
+    ttl: Duration,
+    entries: RwLock<HashMap<String, Entry>>,
+}
+
+struct Entry {
+    categories: Arc<Vec<Category>>,
+    fetched_at: Instant,
+    refreshing: bool,
+}
+
+/// نتیجهٔ نگاه به کش
+pub enum Lookup {
+    Fresh(Arc<Vec<Category>>),
+    /// منقضی شده؛ `true` یعنی این فراخوان باید به‌روزرسانی را شروع کند
+    Stale(Arc<Vec<Category>>, bool),
+    Missing,
+}
+
+impl CategoryCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// نگاه به کش؛ برای مقدار منقضی، فقط اولین فراخوان مسئول به‌روزرسانی می‌شود
+    pub fn lookup(&self, chat_id: &str) -> Lookup {
+        let Ok(mut entries) = self.entries.write() else {
+            return Lookup::Missing;
+        };
+        let Some(entry) = entries.get_mut(chat_id) else {
+            return Lookup::Missing;
+        };
+        if entry.fetched_at.elapsed() < self.ttl {
+            return Lookup::Fresh(entry.categories.clone());
+        }
+        let should_refresh = !entry.refreshing;
+        entry.refreshing = true;
+        Lookup::Stale(entry.categories.clone(), should_refresh)
+    }
+
+    pub fn put(&self, chat_id: &str, categories: Vec<Category>) -> Arc<Vec<Category>> {
+        let categories = Arc::new(categories);
+        if let Ok(mut entries) = self.entries.write() {
+            entries.insert(
+                chat_id.to_string(),
+                Entry {
+                    categories: categories.clone(),
+                    fetched_at: Instant::now(),
+                    refreshing: false,
+                },
+            );
+        }
+        categories
+    }
+
+    /// پایان بی‌نتیجهٔ به‌روزرسانی پس‌زمینه؛ فراخوان بعدی دوباره تلاش می‌کند
+    fn stop_refreshing(&self, chat_id: &str) {
+        if let Ok(mut entries) = self.entries.write()
+            && let Some(entry) = entries.get_mut(chat_id)
+        {
+            entry.refreshing = false;
+        }
+    }
+
+    /// آیا هنوز همان نسخه‌ای است که به‌روزرسانی برایش شروع شد (یعنی بین راه پاک یا عوض نشده)
+    fn is_same(&self, chat_id: &str, categories: &Arc<Vec<Category>>) -> bool {
+        self.entries
+            .read()
+            .ok()
+            .and_then(|e| e.get(chat_id).map(|entry| Arc::ptr_eq(&entry.categories, categories)))
+            .unwrap_or(false)
+    }
+
+    /// افزودن/جایگزینی یک دسته در کش موجود (بعد از ساخت یا ویرایش از ربات)؛
+    /// اگر کشی نباشد کاری نمی‌کند چون خواندن بعدی همه را از پنل می‌آورد
+    pub fn upsert(&self, chat_id: &str, category: Category) {
+        if let Ok(mut entries) = self.entries.write()
+            && let Some(entry) = entries.get_mut(chat_id)
+        {
+            let mut categories: Vec<Category> = entry.categories.as_ref().clone();
+            match categories.iter_mut().find(|c| c.id == category.id) {
+                Some(existing) => *existing = category,
+                None => categories.push(category),
+            }
+            entry.categories = Arc::new(categories);
+        }
+    }
+
+    pub fn invalidate(&self, chat_id: &str) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.remove(chat_id);
+        }
+    }
+}
+
+static CATEGORY_CACHE: OnceLock<CategoryCache> = OnceLock::new();
+
+/// کش سراسری با مدت اعتبار از تنظیمات
+pub fn category_cache() -> &'static CategoryCache {
+    CATEGORY_CACHE.get_or_init(|| CategoryCache::new(config().category_cache_ttl()))
+}
+
+/// دسته‌بندی‌های یک چت از کش؛ فقط وقتی چیزی در کش نیست منتظر پنل می‌ماند
+pub async fn cached_categories(chat_id: &str) -> TorobResult<Arc<Vec<Category>>> {
+    let cache = category_cache();
+    match cache.lookup(chat_id) {
+        Lookup::Fresh(categories) => Ok(categories),
+        Lookup::Stale(categories, should_refresh) => {
+            if should_refresh {
+                let chat_id = chat_id.to_string();
+                let stale = categories.clone();
+                tokio::spawn(async move {
+                    match fetch_categories_from_service(&chat_id).await {
+                        Ok(fresh) if cache.is_same(&chat_id, &stale) => {
+                            cache.put(&chat_id, fresh);
+                        }
+                        // بین راه اعتبارنامه یا خود کش عوض شده؛ نتیجه دور ریخته می‌شود
+                        Ok(_) => cache.stop_refreshing(&chat_id),
+                        Err(e) => {
+                            eprintln!("cannot refresh categories of {}: {}", chat_id, e);
+                            cache.stop_refreshing(&chat_id);
+                        }
+                    }
+                });
+            }
+            Ok(categories)
+        }
+        Lookup::Missing => refresh_categories(chat_id).await,
+    }
+}
+
+/// خواندن دوباره از پنل و جایگزینی کش (دستور /refreshcategories)
+pub async fn refresh_categories(chat_id: &str) -> TorobResult<Arc<Vec<Category>>> {
+    let categories = fetch_categories_from_service(chat_id).await?;
+    Ok(category_cache().put(chat_id, categories))
+}
+
+#[cfg(test)]
+mod test_category_cache {
+    use super::*;
+
+    #[test]
+    fn test_fresh_stale_and_invalidate() {
+        let cache = CategoryCache::new(Duration::from_secs(60));
+        assert!(matches!(cache.lookup("1"), Lookup::Missing));
+        cache.put("1", vec![Category::for_test(1, "c1", None, true)]);
+        assert!(matches!(cache.lookup("1"), Lookup::Fresh(c) if c.len() == 1));
+        cache.upsert("1", Category::for_test(2, "c2", None, true));
+        assert!(matches!(cache.lookup("1"), Lookup::Fresh(c) if c.len() == 2));
+        cache.invalidate("1");
+        assert!(matches!(cache.lookup("1"), Lookup::Missing));
+        cache.upsert("1", Category::for_test(3, "c3", None, true));
+        assert!(matches!(cache.lookup("1"), Lookup::Missing));
+
+        // منقضی: فقط اولین فراخوان به‌روزرسانی را شروع می‌کند
+        let stale = CategoryCache::new(Duration::ZERO);
+        let first = stale.put("1", vec![Category::for_test(1, "c1", None, true)]);
+        assert!(matches!(stale.lookup("1"), Lookup::Stale(_, true)));
+        assert!(matches!(stale.lookup("1"), Lookup::Stale(_, false)));
+        assert!(stale.is_same("1", &first));
+        stale.stop_refreshing("1");
+        assert!(matches!(stale.lookup("1"), Lookup::Stale(_, true)));
+    }
+}
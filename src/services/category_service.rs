@@ -1,119 +1,71 @@
-use std::ascii::AsciiExt;
-use crate::services::models::category::Category;
+use crate::services::models::category::{Category, CategoryCreate};
 use crate::services::tools_method::value_to_category;
-use crate::utilities::site::get_site;
-use crate::utilities::token::get_token;
-use reqwest::header::{ACCEPT, CONTENT_TYPE, ORIGIN, REFERER, USER_AGENT};
+use crate::services::torob_client::TorobClient;
+use crate::services::torob_error::{TorobError, TorobResult};
+use reqwest::Method;
 use serde_json::Value;
 
-/// همهٔ صفحات را می‌خواند و فقط لیست Category برمی‌گرداند؛
-pub async fn fetch_categories_from_service(chat_id: &str)
-    -> Result<Vec<Category>, Box<dyn serde::ser::StdError + Send + Sync + 'static>> {
-    let base = get_site(chat_id).unwrap().trim_end_matches('/').to_string();
-    let token = get_token(chat_id).unwrap();
-
-    let mut start_path_or_url = format!("{}/api/management/v1/categories/?page=1", base);
-
-    // let mut next_url: String = if start_path_or_url.starts_with("https") {
-    //     start_path_or_url.to_string()
-    // } else {
-    //     format!("{base}{start_path_or_url}")
-    // };
-
-    // let endpoint = start_path_or_url.clone();
-    let referer = format!("{}/admin/", base);
-    let origin = base.trim_end_matches('/').to_string();
-
-    let mut out: Vec<Category> = Vec::new();
-
-    let http_client = reqwest::Client::new();
-
-    loop {
-        let resp: reqwest::Response = http_client
-            .get(&start_path_or_url)
-            .header(REFERER, &referer)
-            .header(ORIGIN, &origin)
-            .header(ACCEPT, "application/json")
-            .header(USER_AGENT, "reqwest")
-            .header(reqwest::header::AUTHORIZATION, format!("Api-Key {}", token))
-            .send()
+impl TorobClient {
+    /// همهٔ صفحات دسته‌بندی‌ها را می‌خواند و فقط لیست Category برمی‌گرداند
+    pub async fn fetch_categories(&self) -> TorobResult<Vec<Category>> {
+        let items = self
+            .get_paginated("/api/management/v1/categories/?page=1")
             .await?;
+        Ok(items.iter().filter_map(value_to_category).collect())
+    }
 
-        let ct: String = resp
-            .headers()
-            .get(CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_owned())
-            .unwrap_or_default();
-
-        let text = resp.text().await?;
-        if ct.contains("application/json") == false
-            && !text.trim_start().starts_with('{')
-            && !text.trim_start().starts_with('[')
-        {
-            let preview = text.chars().take(400).collect::<String>();
-            // به‌جای format!(...).into() از io::Error استفاده کنید:
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!(
-                    "unexpected content-type/body ({}). preview: {}",
-                    ct, preview
-                ),
-            )
-            .into());
-        }
-
-        let root: Value = serde_json::from_str(&text)?;
-
-        let items_opt: Option<&Vec<Value>> =
-            if let Some(arr) = root.get("results").and_then(|v| v.as_array()) {
-                Some(arr)
-            } else if let Some(arr) = root.get("result").and_then(|v| v.as_array()) {
-                Some(arr)
-            } else if let Some(arr) = root.as_array() {
-                Some(arr)
-            } else {
-                None
-            };
+    /// خواندن یک دسته‌بندی با شناسه
+    pub async fn get_category(&self, id: u64) -> TorobResult<Category> {
+        let body = self
+            .get_json(&format!("/api/management/v1/categories/{}/", id))
+            .await?;
+        value_to_category(&body).ok_or_else(|| {
+            TorobError::InvalidResponse(format!("cannot parse category {}: {}", id, body))
+        })
+    }
 
-        if let Some(items) = items_opt {
-            for it in items {
-                if let Some(cat) = value_to_category(it) {
-                    out.push(cat);
-                }
-            }
-        } else {
-            let preview = root.to_string();
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!(
-                    "unrecognized JSON shape (no results/result array). preview: {}",
-                    preview.chars().take(400).collect::<String>()
-                ),
-            )
-            .into());
+    /// ساخت دسته‌بندی جدید
+    pub async fn create_category(&self, category: &CategoryCreate) -> TorobResult<Category> {
+        let body = serde_json::to_value(category)
+            .map_err(|e| TorobError::InvalidResponse(e.to_string()))?;
+        let body = self
+            .send_json(Method::POST, "/api/management/v1/categories/", &body)
+            .await?;
+        if let Some(c) = value_to_category(&body).filter(|c| !c.name.is_empty()) {
+            return Ok(c);
         }
-
-        let root_next = root.get("next").and_then(|x| x.as_str());
-
-        if root_next.is_none() == false {
-            if start_path_or_url.eq_ignore_ascii_case(root_next.unwrap()) {
-                break;
-            }
+        // برخی پاسخ‌ها فقط شناسه دارند
+        match TorobClient::extract_id(&body) {
+            Some(id) => Ok(Category {
+                id,
+                name: category.name.clone(),
+                parent: category.parent,
+                available: category.available,
+            }),
+            None => Err(TorobError::InvalidResponse(format!(
+                "category created but could not extract id. body: {}",
+                body
+            ))),
         }
+    }
 
-        if let Some(nv) = root_next {
-            if !nv.is_empty() {
-                start_path_or_url = if nv.starts_with("https") {
-                    nv.to_string()
-                } else {
-                    format!("{base}{nv}")
-                };
-                continue;
-            }
+    /// تغییر بخشی از یک دسته‌بندی (مثلاً `{"name": ...}` یا `{"available": ...}`)
+    pub async fn update_category(&self, id: u64, patch: &Value) -> TorobResult<Category> {
+        let body = self
+            .send_json(
+                Method::PATCH,
+                &format!("/api/management/v1/categories/{}/", id),
+                patch,
+            )
+            .await?;
+        match value_to_category(&body).filter(|c| !c.name.is_empty()) {
+            Some(c) => Ok(c),
+            None => self.get_category(id).await,
         }
-        break;
     }
+}
 
-    Ok(out)
+/// همهٔ صفحات را می‌خواند و فقط لیست Category برمی‌گرداند؛
+pub async fn fetch_categories_from_service(chat_id: &str) -> TorobResult<Vec<Category>> {
+    TorobClient::for_chat(chat_id)?.fetch_categories().await
 }
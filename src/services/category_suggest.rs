@@ -0,0 +1,152 @@
+use crate::services::models::category::Category;
+use crate::utilities::database::{database, SharedConnection};
+use crate::utilities::text::normalize_search;
+use rusqlite::params;
+use std::collections::{HashMap, HashSet};
+
+/// حداکثر انتخاب‌های به‌خاطرسپرده‌شده برای هر چت
+const HISTORY_LIMIT: i64 = 300;
+
+/// کلمات رایجی که در تشخیص دسته بی‌اثرند
+const STOP_WORDS: &[&str] = &["و", "با", "برای", "از", "در", "به", "مدل", "طرح", "the", "for", "and", "with"];
+
+/// ====== پیشنهاد دسته‌بندی از روی نام محصول ======
+///
+/// امتیاز هر دسته = هم‌پوشانی کلمات نام محصول با نام دسته
+/// + دسته‌هایی که همین چت قبلاً برای نام‌های مشابه انتخاب کرده است.
+pub fn suggest_categories(
+    cats: &[Category],
+    product_name: &str,
+    history: &[Choice],
+    limit: usize,
+) -> Vec<u64> {
+    let words = tokens(product_name);
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scores: HashMap<u64, f32> = HashMap::new();
+    for c in cats.iter().filter(|c| c.available) {
+        let overlap = tokens(&c.name)
+            .iter()
+            .filter(|t| words.iter().any(|w| similar(w, t)))
+            .count();
+        if overlap > 0 {
+            *scores.entry(c.id).or_default() += 3.0 * overlap as f32;
+        }
+    }
+
+    for (past, category) in history {
+        let shared = past.iter().filter(|p| words.iter().any(|w| similar(w, p))).count();
+        if shared > 0 && cats.iter().any(|c| c.id == *category && c.available) {
+            *scores.entry(*category).or_default() += 5.0 * shared as f32 / words.len() as f32;
+        }
+    }
+
+    let mut ranked: Vec<(u64, f32)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked.into_iter().take(limit).map(|(id, _)| id).collect()
+}
+
+/// کلمات نرمال‌شدهٔ یک نام (بدون کلمات رایج و تک‌حرفی)
+pub fn tokens(s: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    normalize_search(s)
+        .split(' ')
+        .filter(|w| w.chars().count() > 1 && !STOP_WORDS.contains(w))
+        .filter(|w| seen.insert(w.to_string()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// برابر، یا یکی پیشوند دیگری (مثلاً «کفش» و «کفشهای»)
+fn similar(a: &str, b: &str) -> bool {
+    a == b || (a.chars().count() >= 3 && b.chars().count() >= 3 && (a.starts_with(b) || b.starts_with(a)))
+}
+
+/// یک انتخاب قبلی: (کلمات نام محصول، دسته)
+pub type Choice = (Vec<String>, u64);
+
+/// ثبت دستهٔ انتخاب‌شده برای یک محصول ساخته‌شده (جدول `category_choices`)
+pub fn remember_choice(chat_id: i64, product_name: &str, category: u64) {
+    if let Err(e) = save_choice(database(), chat_id, product_name, category) {
+        eprintln!("could not remember category choice of chat {}: {}", chat_id, e);
+    }
+}
+
+pub fn chat_history(chat_id: i64) -> Vec<Choice> {
+    match load_history(database(), chat_id) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("could not read category choices of chat {}: {}", chat_id, e);
+            Vec::new()
+        }
+    }
+}
+
+fn save_choice(conn: &SharedConnection, chat_id: i64, product_name: &str, category: u64) -> rusqlite::Result<()> {
+    let words = tokens(product_name);
+    if words.is_empty() {
+        return Ok(());
+    }
+    let conn = conn.lock().map_err(|_| rusqlite::Error::InvalidQuery)?;
+    conn.execute(
+        "INSERT INTO category_choices (chat_id, words, category_id) VALUES (?1, ?2, ?3)",
+        params![chat_id, words.join(" "), category as i64],
+    )?;
+    // فقط `HISTORY_LIMIT` انتخاب آخر هر چت می‌ماند
+    conn.execute(
+        "DELETE FROM category_choices WHERE chat_id = ?1 AND id NOT IN
+             (SELECT id FROM category_choices WHERE chat_id = ?1 ORDER BY id DESC LIMIT ?2)",
+        params![chat_id, HISTORY_LIMIT],
+    )?;
+    Ok(())
+}
+
+fn load_history(conn: &SharedConnection, chat_id: i64) -> rusqlite::Result<Vec<Choice>> {
+    let conn = conn.lock().map_err(|_| rusqlite::Error::InvalidQuery)?;
+    let mut stmt = conn.prepare(
+        "SELECT words, category_id FROM category_choices WHERE chat_id = ?1 ORDER BY id",
+    )?;
+    stmt.query_map(params![chat_id], |row| {
+        let words: String = row.get(0)?;
+        let category: i64 = row.get(1)?;
+        Ok((words.split(' ').map(str::to_string).collect(), category as u64))
+    })?
+    .collect()
+}
+
+#[cfg(test)]
+mod test_category_suggest {
+    use super::*;
+
+    #[test]
+    fn test_overlap_and_history() {
+        let cats = vec![
+            Category::for_test(1, "کفش ورزشی", None, true),
+            Category::for_test(2, "کیف", None, true),
+            Category::for_test(3, "کفش رسمی", None, false),
+            Category::for_test(4, "پوشاک مردانه", None, true),
+        ];
+
+        // «ك» عربی و کلمهٔ رایج «مدل» بی‌اثر است
+        assert_eq!(suggest_categories(&cats, "كفش مدل ورزشی نایک", &[], 3), vec![1]);
+        assert!(suggest_categories(&cats, "لپتاپ", &[], 3).is_empty());
+
+        // تاریخچه: «نایک» قبلاً در پوشاک مردانه ثبت شده
+        let history = vec![(tokens("تیشرت نایک"), 4), (tokens("ساعت"), 2)];
+        assert_eq!(suggest_categories(&cats, "تیشرت نایک", &history, 3), vec![4]);
+        assert_eq!(suggest_categories(&cats, "کفش نایک", &history, 3), vec![1, 4]);
+    }
+
+    #[test]
+    fn test_history_is_stored_per_chat() {
+        let conn = crate::utilities::database::open_in_memory().unwrap();
+        save_choice(&conn, 1, "تیشرت نایک", 4).unwrap();
+        save_choice(&conn, 2, "ساعت", 2).unwrap();
+        save_choice(&conn, 1, "و", 9).unwrap();
+
+        assert_eq!(load_history(&conn, 1).unwrap(), vec![(tokens("تیشرت نایک"), 4)]);
+        assert_eq!(load_history(&conn, 2).unwrap().len(), 1);
+    }
+}
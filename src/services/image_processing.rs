@@ -0,0 +1,197 @@
+use crate::utilities::config::ImageConfig;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader, Rgb, RgbImage};
+use std::fmt;
+use std::io::Cursor;
+
+/// پایین‌ترین کیفیت JPEG قبل از اینکه ابعاد کوچک‌تر شود
+const MIN_QUALITY: u8 = 40;
+
+/// ابعادی که کمتر از آن برای جا شدن در حجم کوچک نمی‌کنیم
+const MIN_DIMENSION: u32 = 256;
+
+/// تصویر آمادهٔ آپلود
+#[derive(Debug)]
+pub struct ProcessedImage {
+    pub bytes: Vec<u8>,
+    /// پسوند قالب خروجی (همیشه `jpg`)
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug)]
+pub enum ImageProcessError {
+    /// فایل قابل خواندن نبود
+    Decode(String),
+    /// حتی با کمترین کیفیت و ابعاد در `max_size_bytes` جا نشد
+    TooLarge,
+    Encode(String),
+}
+
+impl fmt::Display for ImageProcessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageProcessError::Decode(why) => write!(f, "cannot decode image: {}", why),
+            ImageProcessError::TooLarge => write!(f, "image does not fit the size limit"),
+            ImageProcessError::Encode(why) => write!(f, "cannot encode image: {}", why),
+        }
+    }
+}
+
+impl ImageProcessError {
+    pub fn user_message(&self) -> String {
+        match self {
+            ImageProcessError::Decode(_) => "❌ تصویر خراب است یا قابل خواندن نیست.".into(),
+            ImageProcessError::TooLarge => {
+                "❌ تصویر حتی بعد از کوچک‌سازی از حد مجاز بزرگ‌تر است.".into()
+            }
+            ImageProcessError::Encode(_) => "❌ پردازش تصویر ناموفق بود.".into(),
+        }
+    }
+}
+
+/// ====== آماده‌سازی تصویر قبل از آپلود ======
+///
+/// خواندن (با اعمال چرخش EXIF)، کوچک‌سازی تا `max_dimension`، و رمزگذاری دوباره با
+/// کیفیت نزولی تا در `max_size_bytes` جا شود؛ اگر جا نشد ابعاد کوچک‌تر می‌شود.
+/// چون تصویر از نو رمزگذاری می‌شود هیچ متادیتایی (EXIF/GPS) به خروجی نمی‌رسد.
+/// خروجی همیشه JPEG است؛ encoder WebP کتابخانهٔ image فقط بی‌اتلاف است و حجم را پایین نمی‌آورد.
+pub fn process_image(bytes: &[u8], cfg: &ImageConfig) -> Result<ProcessedImage, ImageProcessError> {
+    let mut image = decode(bytes)?;
+    if image.width().max(image.height()) > cfg.max_dimension {
+        image = image.resize(cfg.max_dimension, cfg.max_dimension, FilterType::Lanczos3);
+    }
+
+    loop {
+        for quality in quality_steps(cfg) {
+            let encoded = encode(&image, quality)?;
+            if encoded.len() as u64 <= cfg.max_size_bytes {
+                return Ok(ProcessedImage {
+                    bytes: encoded,
+                    extension: "jpg",
+                    width: image.width(),
+                    height: image.height(),
+                });
+            }
+        }
+
+        let longest = image.width().max(image.height());
+        if longest <= MIN_DIMENSION {
+            return Err(ImageProcessError::TooLarge);
+        }
+        let target = (longest * 3 / 4).max(MIN_DIMENSION);
+        image = image.resize(target, target, FilterType::Lanczos3);
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<DynamicImage, ImageProcessError> {
+    let decode_error = |e: image::ImageError| ImageProcessError::Decode(e.to_string());
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| ImageProcessError::Decode(e.to_string()))?
+        .into_decoder()
+        .map_err(decode_error)?;
+    // عکس‌های موبایل معمولاً چرخش را فقط در EXIF دارند؛ قبل از حذف متادیتا اعمالش می‌کنیم
+    let orientation = decoder.orientation().map_err(decode_error)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(decode_error)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// کیفیت‌هایی که به ترتیب امتحان می‌شوند
+fn quality_steps(cfg: &ImageConfig) -> Vec<u8> {
+    let mut steps: Vec<u8> = (MIN_QUALITY..=cfg.quality.max(MIN_QUALITY)).rev().step_by(10).collect();
+    if steps.last() != Some(&MIN_QUALITY) {
+        steps.push(MIN_QUALITY);
+    }
+    steps
+}
+
+fn encode(image: &DynamicImage, quality: u8) -> Result<Vec<u8>, ImageProcessError> {
+    let mut out = Vec::new();
+    flatten(image)
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut out, quality))
+        .map_err(|e| ImageProcessError::Encode(e.to_string()))?;
+    Ok(out)
+}
+
+/// JPEG شفافیت ندارد؛ پیکسل‌های شفاف روی زمینهٔ سفید قرار می‌گیرند
+fn flatten(image: &DynamicImage) -> RgbImage {
+    if !image.color().has_alpha() {
+        return image.to_rgb8();
+    }
+    let rgba = image.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+#[cfg(test)]
+mod test_image_processing {
+    use super::*;
+    use crate::utilities::image_format::ImageFormat;
+    use image::{ImageFormat as Codec, RgbaImage};
+
+    fn noisy_png(width: u32, height: u32) -> Vec<u8> {
+        let mut seed: u32 = 7;
+        let img = RgbaImage::from_fn(width, height, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let [r, g, b, _] = seed.to_le_bytes();
+            image::Rgba([r, g, b, 255])
+        });
+        let mut out = Vec::new();
+        img.write_to(&mut Cursor::new(&mut out), Codec::Png).unwrap();
+        out
+    }
+
+    /// JPEG کوچک با بخش EXIF که می‌گوید تصویر ۹۰ درجه چرخیده است (orientation = 6)
+    fn rotated_jpeg() -> Vec<u8> {
+        let mut jpeg = Vec::new();
+        RgbImage::new(40, 20)
+            .write_to(&mut Cursor::new(&mut jpeg), Codec::Jpeg)
+            .unwrap();
+        let tiff: &[u8] = &[
+            b'M', b'M', 0, 42, 0, 0, 0, 8, // هدر و آفست IFD
+            0, 1, // یک ورودی
+            0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0, // Orientation = 6
+            0, 0, 0, 0,
+        ];
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend_from_slice(tiff);
+        let len = (app1.len() + 2) as u16;
+        let mut out = jpeg[..2].to_vec();
+        out.extend_from_slice(&[0xFF, 0xE1]);
+        out.extend_from_slice(&len.to_be_bytes());
+        out.extend_from_slice(&app1);
+        out.extend_from_slice(&jpeg[2..]);
+        out
+    }
+
+    #[test]
+    fn test_downscale_fit_and_strip_exif() {
+        let cfg = ImageConfig {
+            max_size_bytes: 150 * 1024,
+            max_dimension: 800,
+            ..ImageConfig::default()
+        };
+        let out = process_image(&noisy_png(1200, 900), &cfg).unwrap();
+        assert!(out.bytes.len() as u64 <= cfg.max_size_bytes);
+        assert!(out.width.max(out.height) <= 800);
+        assert_eq!(out.width * 3, out.height * 4);
+        assert_eq!(ImageFormat::sniff(&out.bytes), Some(ImageFormat::Jpeg));
+
+        // چرخش اعمال می‌شود و خود EXIF در خروجی نیست
+        let out = process_image(&rotated_jpeg(), &ImageConfig::default()).unwrap();
+        assert_eq!((out.width, out.height), (20, 40));
+        assert!(!out.bytes.windows(4).any(|w| w == b"Exif"));
+
+        assert!(matches!(
+            process_image(b"not an image", &cfg),
+            Err(ImageProcessError::Decode(_))
+        ));
+    }
+}
@@ -3,4 +3,11 @@ pub mod product_service;
 pub mod category_service;
 pub mod product_image_service;
 pub mod tools_method;
-pub mod accounting;
\ No newline at end of file
+pub mod accounting;
+pub mod torob_client;
+pub mod torob_error;
+pub mod panel_probe;
+pub mod trash;
+pub mod category_cache;
+pub mod category_suggest;
+pub mod image_processing;
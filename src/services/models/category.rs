@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 /// ======= دسته بندی =======
@@ -10,12 +10,33 @@ pub struct Category {
     pub available: bool,
 }
 
+#[cfg(test)]
+impl Category {
+    /// سازندهٔ کوتاه برای تست‌ها
+    pub fn for_test(id: u64, name: &str, parent: Option<u64>, available: bool) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            parent,
+            available,
+        }
+    }
+}
+
+/// ======= ساخت دسته بندی =======
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryCreate {
+    pub name: String,
+    pub parent: Option<u64>,
+    pub available: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CategoriesPage {
-    next: Option<String>,
-    previous: Option<String>,
-    total_pages: Option<u32>, // گاهی ممکن است نیاید
-    current_page: Option<u32>,
-    per_page: Option<u32>,
-    results: Vec<Value>, // نتایج را بعداً منعطف تبدیل می‌کنیم
+    pub next: Option<String>,
+    pub previous: Option<String>,
+    pub total_pages: Option<u32>, // گاهی ممکن است نیاید
+    pub current_page: Option<u32>,
+    pub per_page: Option<u32>,
+    pub results: Vec<Value>, // نتایج را بعداً منعطف تبدیل می‌کنیم
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
+use crate::utilities::text::parse_number;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// نوع شمارشی برای وضعیت موجودی
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -154,4 +156,408 @@ impl ProductCreate {
             extra_fields: None,
         }
     }
-}
\ No newline at end of file
+}
+/// ======= محصول موجود در فروشگاه =======
+/// شناسه + همان فیلدهای `ProductCreate` که از پاسخ API خوانده شده‌اند
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Product {
+    pub id: u64,
+    pub fields: ProductCreate,
+}
+
+/// ======= فیلتر لیست محصولات =======
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductQuery {
+    /// جستجوی متنی (نام، بارکد، ...)
+    pub search: Option<String>,
+    /// فقط محصولات این دسته‌بندی
+    pub category: Option<u64>,
+    /// فقط فعال‌ها / غیرفعال‌ها
+    pub available: Option<bool>,
+    /// شمارهٔ صفحه (از 1)
+    pub page: u32,
+}
+
+/// ======= یک صفحه از لیست محصولات =======
+#[derive(Debug, Clone)]
+pub struct ProductPage {
+    pub items: Vec<Product>,
+    pub page: u32,
+    pub total_pages: Option<u32>,
+    pub count: Option<u64>,
+    pub has_next: bool,
+    pub has_previous: bool,
+}
+
+/// ====== فیلدهای قابل ویرایش/پرسش محصول ======
+/// هر مورد به یک فیلد `ProductCreate` نگاشت می‌شود؛ تبدیل متن کاربر به مقدار و اعتبارسنجی همین‌جاست.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductField {
+    Name,
+    Price,
+    CompareAtPrice,
+    MainCategory,
+    OtherCategories,
+    Description,
+    EnglishName,
+    Barcode,
+    StockType,
+    Stock,
+    Available,
+    IsDigital,
+    SpecialOffer,
+    Weight,
+    Length,
+    Width,
+    Height,
+    MaxOrderQuantity,
+    Guarantee,
+    SeoTitle,
+    SeoDescription,
+}
+
+impl ProductField {
+    pub const ALL: &'static [ProductField] = &[
+        ProductField::Name,
+        ProductField::Price,
+        ProductField::CompareAtPrice,
+        ProductField::MainCategory,
+        ProductField::OtherCategories,
+        ProductField::Description,
+        ProductField::EnglishName,
+        ProductField::Barcode,
+        ProductField::StockType,
+        ProductField::Stock,
+        ProductField::Available,
+        ProductField::IsDigital,
+        ProductField::SpecialOffer,
+        ProductField::Weight,
+        ProductField::Length,
+        ProductField::Width,
+        ProductField::Height,
+        ProductField::MaxOrderQuantity,
+        ProductField::Guarantee,
+        ProductField::SeoTitle,
+        ProductField::SeoDescription,
+    ];
+
+    /// فیلدهای اختیاری مسیر «پیشرفته» در ساخت محصول (نام، قیمت و دسته‌بندی‌ها قبلاً پرسیده شده‌اند)
+    pub const ADVANCED: &'static [ProductField] = &[
+        ProductField::CompareAtPrice,
+        ProductField::Description,
+        ProductField::EnglishName,
+        ProductField::Barcode,
+        ProductField::StockType,
+        ProductField::Stock,
+        ProductField::Available,
+        ProductField::IsDigital,
+        ProductField::SpecialOffer,
+        ProductField::Weight,
+        ProductField::Length,
+        ProductField::Width,
+        ProductField::Height,
+        ProductField::MaxOrderQuantity,
+        ProductField::Guarantee,
+        ProductField::SeoTitle,
+        ProductField::SeoDescription,
+    ];
+
+    /// نام فیلد در API (همان نام سریال‌شدهٔ `ProductCreate`)
+    pub fn key(self) -> &'static str {
+        match self {
+            ProductField::Name => "name",
+            ProductField::Price => "price",
+            ProductField::CompareAtPrice => "compare_at_price",
+            ProductField::MainCategory => "main_category",
+            ProductField::OtherCategories => "other_categories",
+            ProductField::Description => "description",
+            ProductField::EnglishName => "english_name",
+            ProductField::Barcode => "barcode",
+            ProductField::StockType => "stock_type",
+            ProductField::Stock => "stock",
+            ProductField::Available => "available",
+            ProductField::IsDigital => "is_digital",
+            ProductField::SpecialOffer => "special_offer",
+            ProductField::Weight => "weight",
+            ProductField::Length => "length",
+            ProductField::Width => "width",
+            ProductField::Height => "height",
+            ProductField::MaxOrderQuantity => "max_order_quantity",
+            ProductField::Guarantee => "guarantee",
+            ProductField::SeoTitle => "seo_title",
+            ProductField::SeoDescription => "seo_description",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|f| f.key() == key)
+    }
+
+    /// عنوان فارسی برای دکمه‌ها و پیام‌ها
+    pub fn label(self) -> &'static str {
+        match self {
+            ProductField::Name => "نام",
+            ProductField::Price => "قیمت",
+            ProductField::CompareAtPrice => "قیمت قبل از تخفیف",
+            ProductField::MainCategory => "دسته‌بندی اصلی",
+            ProductField::OtherCategories => "دسته‌بندی‌های دیگر",
+            ProductField::Description => "توضیحات",
+            ProductField::EnglishName => "نام انگلیسی",
+            ProductField::Barcode => "بارکد",
+            ProductField::StockType => "نوع موجودی",
+            ProductField::Stock => "موجودی",
+            ProductField::Available => "فعال",
+            ProductField::IsDigital => "محصول دیجیتال",
+            ProductField::SpecialOffer => "پیشنهاد ویژه",
+            ProductField::Weight => "وزن (گرم)",
+            ProductField::Length => "طول (سانتی‌متر)",
+            ProductField::Width => "عرض (سانتی‌متر)",
+            ProductField::Height => "ارتفاع (سانتی‌متر)",
+            ProductField::MaxOrderQuantity => "حداکثر تعداد در سبد",
+            ProductField::Guarantee => "گارانتی",
+            ProductField::SeoTitle => "عنوان سئو",
+            ProductField::SeoDescription => "توضیحات سئو",
+        }
+    }
+
+    /// راهنمای ورود مقدار
+    pub fn prompt(self) -> String {
+        let hint = match self {
+            ProductField::StockType => "یکی از: limited، unlimited، call، out_of_stock",
+            ProductField::OtherCategories => "شناسه‌ها با کاما جدا شوند",
+            ProductField::Available | ProductField::IsDigital | ProductField::SpecialOffer => {
+                "بله یا خیر"
+            }
+            ProductField::Price
+            | ProductField::CompareAtPrice
+            | ProductField::MainCategory
+            | ProductField::Stock
+            | ProductField::Weight
+            | ProductField::Length
+            | ProductField::Width
+            | ProductField::Height
+            | ProductField::MaxOrderQuantity => "فقط عدد",
+            _ => "متن",
+        };
+        if self.is_required() {
+            format!("{} را وارد کنید ({}).", self.label(), hint)
+        } else {
+            format!(
+                "{} را وارد کنید ({}). برای خالی کردن «-» بفرستید.",
+                self.label(),
+                hint
+            )
+        }
+    }
+
+    /// نام و دسته‌بندی اصلی در `ProductCreate` اجباری‌اند و خالی نمی‌شوند
+    pub fn is_required(self) -> bool {
+        matches!(self, ProductField::Name | ProductField::MainCategory)
+    }
+
+    /// مقدار فعلی فیلد برای نمایش (None یعنی تنظیم نشده)
+    pub fn value_text(self, p: &ProductCreate) -> Option<String> {
+        fn yes_no(b: bool) -> String {
+            if b { "بله".into() } else { "خیر".into() }
+        }
+        match self {
+            ProductField::Name => Some(p.name.clone()),
+            ProductField::Price => p.price.map(|v| v.to_string()),
+            ProductField::CompareAtPrice => p.compare_at_price.map(|v| v.to_string()),
+            ProductField::MainCategory => Some(p.main_category.to_string()),
+            ProductField::OtherCategories => p
+                .other_categories
+                .as_ref()
+                .filter(|ids| !ids.is_empty())
+                .map(|ids| ids.iter().map(u64::to_string).collect::<Vec<_>>().join(", ")),
+            ProductField::Description => p.description.clone(),
+            ProductField::EnglishName => p.english_name.clone(),
+            ProductField::Barcode => p.barcode.clone(),
+            ProductField::StockType => p.stock_type.map(|v| v.key().to_string()),
+            ProductField::Stock => p.stock.map(|v| v.to_string()),
+            ProductField::Available => p.available.map(yes_no),
+            ProductField::IsDigital => p.is_digital.map(yes_no),
+            ProductField::SpecialOffer => p.special_offer.map(yes_no),
+            ProductField::Weight => p.weight.map(|v| v.to_string()),
+            ProductField::Length => p.length.map(|v| v.to_string()),
+            ProductField::Width => p.width.map(|v| v.to_string()),
+            ProductField::Height => p.height.map(|v| v.to_string()),
+            ProductField::MaxOrderQuantity => p.max_order_quantity.map(|v| v.to_string()),
+            ProductField::Guarantee => p.guarantee.clone(),
+            ProductField::SeoTitle => p.seo_title.clone(),
+            ProductField::SeoDescription => p.seo_description.clone(),
+        }
+    }
+
+    /// تبدیل متن کاربر به مقدار و قرار دادن آن در `ProductCreate`؛ خطا پیام فارسی است.
+    ///
+    /// برای فیلدهای اختیاری «-» مقدار را خالی (None) می‌کند.
+    pub fn apply(self, p: &mut ProductCreate, input: &str) -> Result<(), String> {
+        let text = input.trim();
+        if text.is_empty() {
+            return Err(format!("{} نمی‌تواند خالی باشد.", self.label()));
+        }
+        let clear = text == "-";
+        if clear && self.is_required() {
+            return Err(format!("{} اجباری است و خالی نمی‌شود.", self.label()));
+        }
+
+        let number_error = || format!("{} باید یک عدد صحیح نامنفی باشد.", self.label());
+        let u64_value = || -> Result<Option<u64>, String> {
+            if clear {
+                return Ok(None);
+            }
+            parse_number::<u64>(text).map(Some).ok_or_else(number_error)
+        };
+        let u32_value = || -> Result<Option<u32>, String> {
+            if clear {
+                return Ok(None);
+            }
+            parse_number::<u32>(text).map(Some).ok_or_else(number_error)
+        };
+        let bool_value = || -> Result<Option<bool>, String> {
+            if clear {
+                return Ok(None);
+            }
+            parse_yes_no(text)
+                .map(Some)
+                .ok_or_else(|| format!("برای {} «بله» یا «خیر» بفرستید.", self.label()))
+        };
+        let text_value = || if clear { None } else { Some(text.to_string()) };
+
+        match self {
+            ProductField::Name => p.name = text.to_string(),
+            ProductField::Price => p.price = u64_value()?,
+            ProductField::CompareAtPrice => p.compare_at_price = u64_value()?,
+            ProductField::MainCategory => p.main_category = u64_value()?.unwrap_or(p.main_category),
+            ProductField::OtherCategories => {
+                p.other_categories = if clear {
+                    None
+                } else {
+                    let mut ids = Vec::new();
+                    for part in text.split([',', '،', ' ']).filter(|s| !s.is_empty()) {
+                        let id = parse_number::<u64>(part).ok_or_else(number_error)?;
+                        if !ids.contains(&id) {
+                            ids.push(id);
+                        }
+                    }
+                    Some(ids)
+                }
+            }
+            ProductField::Description => p.description = text_value(),
+            ProductField::EnglishName => p.english_name = text_value(),
+            ProductField::Barcode => p.barcode = text_value(),
+            ProductField::StockType => {
+                p.stock_type = if clear {
+                    None
+                } else {
+                    Some(StockType::parse(text).ok_or_else(|| {
+                        "نوع موجودی نامعتبر است؛ limited، unlimited، call یا out_of_stock بفرستید."
+                            .to_string()
+                    })?)
+                }
+            }
+            ProductField::Stock => p.stock = u64_value()?,
+            ProductField::Available => p.available = bool_value()?,
+            ProductField::IsDigital => p.is_digital = bool_value()?,
+            ProductField::SpecialOffer => p.special_offer = bool_value()?,
+            ProductField::Weight => p.weight = u32_value()?,
+            ProductField::Length => p.length = u32_value()?,
+            ProductField::Width => p.width = u32_value()?,
+            ProductField::Height => p.height = u32_value()?,
+            ProductField::MaxOrderQuantity => p.max_order_quantity = u32_value()?,
+            ProductField::Guarantee => p.guarantee = text_value(),
+            ProductField::SeoTitle => p.seo_title = text_value(),
+            ProductField::SeoDescription => p.seo_description = text_value(),
+        }
+        Ok(())
+    }
+
+    /// بدنهٔ PATCH برای همین یک فیلد، با همان سریال‌سازی `ProductCreate` (مقدار خالی = null)
+    pub fn patch_body(self, p: &ProductCreate) -> Value {
+        let value = serde_json::to_value(p)
+            .ok()
+            .and_then(|v| v.get(self.key()).cloned())
+            .unwrap_or(Value::Null);
+        // پاک کردن لیست با آرایهٔ خالی، نه null
+        let value = match (self, value) {
+            (ProductField::OtherCategories, Value::Null) => Value::Array(Vec::new()),
+            (_, value) => value,
+        };
+        let mut body = serde_json::Map::new();
+        body.insert(self.key().to_string(), value);
+        Value::Object(body)
+    }
+}
+
+impl StockType {
+    /// نام در API
+    pub fn key(self) -> &'static str {
+        match self {
+            StockType::Limited => "limited",
+            StockType::Unlimited => "unlimited",
+            StockType::Call => "call",
+            StockType::OutOfStock => "out_of_stock",
+        }
+    }
+
+    /// نام انگلیسی API یا معادل فارسی
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "limited" | "محدود" => Some(StockType::Limited),
+            "unlimited" | "نامحدود" => Some(StockType::Unlimited),
+            "call" | "تماس" | "تماس بگیرید" => Some(StockType::Call),
+            "out_of_stock" | "ناموجود" => Some(StockType::OutOfStock),
+            _ => None,
+        }
+    }
+}
+
+fn parse_yes_no(s: &str) -> Option<bool> {
+    match s.trim().to_lowercase().as_str() {
+        "بله" | "آره" | "yes" | "y" | "true" | "1" | "✅" => Some(true),
+        "خیر" | "نه" | "no" | "n" | "false" | "0" | "❌" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test_product_field {
+    use super::*;
+
+    #[test]
+    fn test_apply_and_patch_body() {
+        let mut p = ProductCreate::new("کفش", 7);
+        ProductField::Price.apply(&mut p, "۲۵۰,۰۰۰").unwrap();
+        assert_eq!(p.price, Some(250000));
+        assert_eq!(
+            ProductField::Price.patch_body(&p),
+            serde_json::json!({"price": 250000})
+        );
+
+        ProductField::StockType.apply(&mut p, "ناموجود").unwrap();
+        assert_eq!(
+            ProductField::StockType.patch_body(&p),
+            serde_json::json!({"stock_type": "out_of_stock"})
+        );
+
+        ProductField::Price.apply(&mut p, "-").unwrap();
+        assert_eq!(ProductField::Price.patch_body(&p), serde_json::json!({"price": null}));
+
+        ProductField::OtherCategories.apply(&mut p, "۳، 4,3").unwrap();
+        assert_eq!(
+            ProductField::OtherCategories.patch_body(&p),
+            serde_json::json!({"other_categories": [3, 4]})
+        );
+        ProductField::OtherCategories.apply(&mut p, "-").unwrap();
+        assert_eq!(
+            ProductField::OtherCategories.patch_body(&p),
+            serde_json::json!({"other_categories": []})
+        );
+
+        assert!(ProductField::Name.apply(&mut p, "-").is_err());
+        assert!(ProductField::Weight.apply(&mut p, "سنگین").is_err());
+        assert_eq!(ProductField::from_key("seo_title"), Some(ProductField::SeoTitle));
+    }
+}
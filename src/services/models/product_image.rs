@@ -17,6 +17,34 @@ pub struct ProductImageCreate {
     pub default: Option<bool>,
 }
 
+impl ProductImageCreate {
+    /// تصویر باینری با نام فایل (MIME از پسوند حدس زده می‌شود)
+    pub fn from_file(pk: u64, filename: impl Into<String>, bytes: Vec<u8>) -> Self {
+        Self {
+            pk,
+            image: Some(ImageFile {
+                filename: filename.into(),
+                bytes,
+                mime: None,
+            }),
+            image_url: None,
+            image_alt: None,
+            default: None,
+        }
+    }
+
+    /// تصویری که سایت خودش از لینک دانلود می‌کند
+    pub fn from_url(pk: u64, url: impl Into<String>) -> Self {
+        Self {
+            pk,
+            image: None,
+            image_url: Some(url.into()),
+            image_alt: None,
+            default: None,
+        }
+    }
+}
+
 /// نگه‌دارندهٔ فایل تصویر برای multipart
 #[derive(Debug, Clone)]
 pub struct ImageFile {
@@ -26,4 +54,13 @@ pub struct ImageFile {
     pub bytes: Vec<u8>,
     /// MIME-Type اختیاری (مثل "image/jpeg" یا "image/png")
     pub mime: Option<String>,
-}
\ No newline at end of file
+}
+/// ======= تصویر ثبت‌شدهٔ یک محصول (از پاسخ API) =======
+#[derive(Debug, Clone)]
+pub struct ProductImage {
+    pub id: u64,
+    /// آدرس فایل تصویر روی سایت
+    pub image: Option<String>,
+    pub image_alt: Option<String>,
+    pub default: bool,
+}
@@ -0,0 +1,110 @@
+use crate::services::torob_client::{TorobClient, shared_http};
+use crate::services::torob_error::{TorobError, TorobResult};
+use reqwest::Url;
+use reqwest::header::{ACCEPT, CONTENT_TYPE, USER_AGENT};
+
+/// ارزان‌ترین endpoint مدیریتی که هم بدون کلید (برای تشخیص پنل) و هم با کلید قابل صدا زدن است
+const PROBE_PATH: &str = "/api/management/v1/categories/?page=1";
+
+/// ====== یکسان‌سازی آدرس پنل ======
+///
+/// فاصله‌ها حذف، در نبود scheme `https://` اضافه و مسیر/کوئری دور ریخته می‌شود؛
+/// خروجی فقط `https://host[:port]` است.
+pub fn normalize_panel_url(input: &str) -> TorobResult<String> {
+    let text = input.trim();
+    if text.is_empty() {
+        return Err(TorobError::InvalidUrl("empty".into()));
+    }
+
+    let with_scheme = if text.contains("://") {
+        text.to_string()
+    } else {
+        format!("https://{}", text)
+    };
+
+    let url = Url::parse(&with_scheme).map_err(|e| TorobError::InvalidUrl(e.to_string()))?;
+    if url.scheme() != "https" {
+        return Err(TorobError::InvalidUrl(format!("scheme {} is not https", url.scheme())));
+    }
+    origin_of(&url)
+}
+
+/// ====== بررسی آدرس پنل ======
+///
+/// بدون کلید، endpoint مدیریت را صدا می‌زند (با دنبال کردن redirectها).
+/// پاسخ JSON (چه 200 چه 401/403) یعنی پنل ترب است؛ خروجی آدرس نهایی پس از redirect است.
+pub async fn probe_site(input: &str) -> TorobResult<String> {
+    let origin = normalize_panel_url(input)?;
+
+    let resp = shared_http()?
+        .get(format!("{}{}", origin, PROBE_PATH))
+        .header(ACCEPT, "application/json")
+        .header(USER_AGENT, "reqwest")
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_connect() || e.is_timeout() || e.is_request() {
+                TorobError::Unreachable(e.to_string())
+            } else {
+                TorobError::Network(e)
+            }
+        })?;
+
+    let status = resp.status();
+    if resp.url().scheme() != "https" {
+        return Err(TorobError::InvalidUrl(format!("redirected to {}", resp.url())));
+    }
+    let final_origin = origin_of(resp.url())?;
+    let is_json = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.contains("application/json"));
+
+    match status.as_u16() {
+        200 | 401 | 403 if is_json => Ok(final_origin),
+        s if status.is_server_error() => Err(TorobError::Server {
+            status: s,
+            preview: String::new(),
+        }),
+        s => Err(TorobError::NotTorobShop { status: s }),
+    }
+}
+
+/// ====== بررسی Api-Key ======
+///
+/// یک درخواست احراز هویت‌شده به همان endpoint؛ کلید ردشده `Unauthorized` برمی‌گرداند.
+pub async fn probe_token(site: &str, token: &str) -> TorobResult<()> {
+    TorobClient::new(site, token)?.get_json(PROBE_PATH).await?;
+    Ok(())
+}
+
+fn origin_of(url: &Url) -> TorobResult<String> {
+    let host = url
+        .host_str()
+        .filter(|h| !h.is_empty())
+        .ok_or_else(|| TorobError::InvalidUrl("missing host".into()))?;
+    Ok(match url.port() {
+        Some(port) => format!("{}://{}:{}", url.scheme(), host, port),
+        None => format!("{}://{}", url.scheme(), host),
+    })
+}
+
+#[cfg(test)]
+mod test_panel_probe {
+    use super::*;
+
+    #[test]
+    fn test_normalize_panel_url() {
+        assert_eq!(normalize_panel_url(" shop.ir ").unwrap(), "https://shop.ir");
+        assert_eq!(
+            normalize_panel_url("https://Shop.ir:8443/admin/?x=1").unwrap(),
+            "https://shop.ir:8443"
+        );
+        assert!(matches!(
+            normalize_panel_url("http://shop.ir"),
+            Err(TorobError::InvalidUrl(_))
+        ));
+        assert!(matches!(normalize_panel_url("https://"), Err(TorobError::InvalidUrl(_))));
+    }
+}
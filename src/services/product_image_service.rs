@@ -1,90 +1,172 @@
-use std::io;
-use reqwest::header::{ACCEPT, ORIGIN, REFERER, USER_AGENT};
+use crate::services::models::product_image::{ProductImage, ProductImageCreate};
+use crate::services::tools_method::value_to_product_image;
+use crate::services::torob_client::{TorobClient, shared_http};
+use crate::services::torob_error::{TorobError, TorobResult};
+use crate::utilities::config::config;
+use reqwest::Method;
 use reqwest::multipart::{Form, Part};
-use crate::utilities::site::get_site;
+use serde_json::Value;
 
-/// آپلود تصویر محصول (فقط فیلد اجباری `image`)
-/// برمی‌گرداند: شناسهٔ تصویر (image_id)
-pub async fn upload_product_image_file(
-    chat_id: String,
-    product_id: u64,
-    filename: &str,
-    image_bytes: Vec<u8>,
-) -> Result<u64, Box<dyn std::error::Error + Send + Sync + 'static>> {
-
-    // POST /api/management/v1/products/{pk}/images/
-    let site = get_site(&chat_id).unwrap();
-    let endpoint = format!("{}/api/management/v1/products/{}/images/", site, product_id);
-    let referer = format!("{}/admin/",site);
-    let origin = site.trim_end_matches('/').to_string();
-
-    // حدس ساده MIME از پسوند فایل (اختیاری اما مفید)
-    let mime = match filename.to_ascii_lowercase().rsplit('.').next() {
-        Some("png") => "image/png",
-        Some("webp") => "image/webp",
-        Some("gif") => "image/gif",
-        _ => "image/jpeg",
-    };
+impl TorobClient {
+    /// آپلود تصویر محصول (فقط فیلد اجباری `image`)
+    /// برمی‌گرداند: شناسهٔ تصویر (image_id)
+    pub async fn upload_product_image(
+        &self,
+        product_id: u64,
+        filename: &str,
+        image_bytes: Vec<u8>,
+    ) -> TorobResult<u64> {
+        self.create_product_image(ProductImageCreate::from_file(product_id, filename, image_bytes))
+            .await
+    }
 
-    // فقط فیلد اجباری `image`
-    let image_part =
-        Part::bytes(image_bytes)
-        .file_name(filename.to_owned())
-        .mime_str(mime)?;
+    /// ثبت تصویر محصول با همهٔ فیلدهای `ProductImageCreate` (فایل یا لینک، alt و پیش‌فرض)
+    /// برمی‌گرداند: شناسهٔ تصویر (image_id)
+    pub async fn create_product_image(&self, payload: ProductImageCreate) -> TorobResult<u64> {
+        let has_url = payload.image_url.as_deref().is_some_and(|u| !u.trim().is_empty());
+        if payload.image.is_none() && !has_url {
+            return Err(TorobError::Validation {
+                fields: vec![("image".into(), vec!["یکی از فایل تصویر یا لینک آن لازم است.".into()])],
+                detail: None,
+            });
+        }
 
-    let form = Form::new().part("image", image_part);
+        let mut form = Form::new();
+        if let Some(file) = payload.image {
+            let mime = file
+                .mime
+                .unwrap_or_else(|| guess_mime(&file.filename).to_string());
+            let image_part = Part::bytes(file.bytes)
+                .file_name(file.filename)
+                .mime_str(&mime)
+                // خطای محلی ساخت فرم است نه خطای شبکه
+                .map_err(|_| TorobError::Validation {
+                    fields: vec![("image".into(), vec![format!("نوع فایل «{}» معتبر نیست.", mime)])],
+                    detail: None,
+                })?;
+            form = form.part("image", image_part);
+        }
+        if let Some(url) = payload.image_url.filter(|_| has_url) {
+            form = form.text("image_url", url.trim().to_string());
+        }
+        if let Some(alt) = payload.image_alt {
+            form = form.text("image_alt", alt);
+        }
+        if let Some(default) = payload.default {
+            form = form.text("default", default.to_string());
+        }
 
-    let token =
-        crate::utilities::token::get_token(chat_id).expect("no token");
+        // POST /api/management/v1/products/{pk}/images/
+        // نمونهٔ پاسخ: { "success": true, "id": 2 }
+        let body = self
+            .post_multipart(&format!("/api/management/v1/products/{}/images/", payload.pk), form)
+            .await?;
 
-    if token.is_empty() {
-        return Err("no token".into());
+        TorobClient::extract_id(&body).ok_or_else(|| {
+            TorobError::InvalidResponse(format!(
+                "image uploaded but could not extract id. body: {}",
+                body
+            ))
+        })
     }
 
-    let client = reqwest::Client::new();
-    
-    let resp = client
-        .post(&endpoint)
-        .header(ACCEPT, "application/json")
-        .header(REFERER, &referer)
-        .header(ORIGIN, &origin)
-        .header(USER_AGENT, "reqwest")
-        .header(reqwest::header::AUTHORIZATION, format!("Api-Key {}", token))
-        .multipart(form)
-        .send()
-        .await?;
-
-    let status = resp.status();
-    let body = resp.text().await?;
+    /// لیست تصاویر یک محصول
+    pub async fn list_product_images(&self, product_id: u64) -> TorobResult<Vec<ProductImage>> {
+        let items = self
+            .get_paginated(&format!("/api/management/v1/products/{}/images/", product_id))
+            .await?;
+        Ok(items.iter().filter_map(value_to_product_image).collect())
+    }
 
-    if !status.is_success() {
-        let preview: String = body.chars().take(400).collect();
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("image upload failed: {} • {}", status, preview),
+    /// تغییر فیلدهای یک تصویر موجود (مثل `{"default": true}` یا `{"image_alt": "..."}`)
+    pub async fn update_product_image(&self, product_id: u64, image_id: u64, body: &Value) -> TorobResult<()> {
+        self.send_json(
+            Method::PATCH,
+            &format!("/api/management/v1/products/{}/images/{}/", product_id, image_id),
+            body,
         )
-            .into());
+        .await?;
+        Ok(())
     }
 
-    // نمونهٔ پاسخ: { "success": true, "id": 2 }
-    #[derive(serde::Deserialize)]
-    struct Resp {
-        id: Option<u64>,
+    /// حذف یک تصویر محصول
+    pub async fn delete_product_image(&self, product_id: u64, image_id: u64) -> TorobResult<()> {
+        self.delete(&format!(
+            "/api/management/v1/products/{}/images/{}/",
+            product_id, image_id
+        ))
+        .await
     }
 
-    if let Ok(r) = serde_json::from_str::<Resp>(&body) {
-        if let Some(id) = r.id {
-            return Ok(id);
+    /// دانلود بایت‌های یک تصویر از آدرس کامل یا نسبی سایت (بدون هدرهای API)
+    ///
+    /// آدرس تصویر از پاسخ پنل می‌آید؛ فقط میزبان خود پنل مجاز است (حتی بعد از redirect) و
+    /// بدنه به صورت جریانی خوانده می‌شود تا از `images.max_input_bytes` بیشتر نشود.
+    pub async fn fetch_image_bytes(&self, url: &str) -> TorobResult<Vec<u8>> {
+        let url = self.url(url);
+        if !self.is_panel_url(&url) {
+            return Err(TorobError::InvalidResponse(format!(
+                "image url {} is not on the panel host",
+                url
+            )));
+        }
+        let mut resp = shared_http()?.get(&url).send().await?;
+        if !self.is_panel_url(resp.url().as_str()) {
+            return Err(TorobError::InvalidResponse(format!(
+                "image url {} redirected off the panel host",
+                url
+            )));
+        }
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(TorobError::from_response(status, None, ""));
+        }
+
+        let limit = config().images.max_input_bytes;
+        let too_large =
+            || TorobError::InvalidResponse(format!("image {} is larger than {} bytes", url, limit));
+        if resp.content_length().is_some_and(|len| len > limit) {
+            return Err(too_large());
         }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            if (bytes.len() + chunk.len()) as u64 > limit {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
     }
-    let v: serde_json::Value = serde_json::from_str(&body)?;
-    if let Some(id) = v.get("id").and_then(|x| x.as_u64()) {
-        return Ok(id);
+}
+
+/// حدس ساده MIME از پسوند فایل
+fn guess_mime(filename: &str) -> &'static str {
+    match filename.to_ascii_lowercase().rsplit('.').next() {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        Some("bmp") => "image/bmp",
+        _ => "image/jpeg",
     }
+}
+
+/// آپلود تصویر محصول (فقط فیلد اجباری `image`)
+/// برمی‌گرداند: شناسهٔ تصویر (image_id)
+pub async fn upload_product_image_file(
+    chat_id: String,
+    product_id: u64,
+    filename: &str,
+    image_bytes: Vec<u8>,
+) -> TorobResult<u64> {
+    TorobClient::for_chat(&chat_id)?
+        .upload_product_image(product_id, filename, image_bytes)
+        .await
+}
 
-    Err(io::Error::new(
-        io::ErrorKind::Other,
-        format!("image uploaded but could not extract id. body: {}", body),
-    )
-        .into())
-}
\ No newline at end of file
+/// ثبت تصویر محصول برای یک چت با همهٔ فیلدهای `ProductImageCreate`
+/// برمی‌گرداند: شناسهٔ تصویر (image_id)
+pub async fn submit_product_image(chat_id: String, payload: ProductImageCreate) -> TorobResult<u64> {
+    TorobClient::for_chat(&chat_id)?
+        .create_product_image(payload)
+        .await
+}
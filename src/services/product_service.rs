@@ -1,7 +1,13 @@
-use crate::utilities::site::get_site;
-use crate::services::models::product::ProductCreate;
+use crate::services::models::product::{Product, ProductCreate, ProductPage, ProductQuery};
+use crate::services::tools_method::{read_product, value_to_product};
+use crate::services::trash::DeletedProduct;
+use crate::services::torob_client::TorobClient;
+use crate::services::torob_error::{TorobError, TorobResult};
+use crate::utilities::text::{normalize_digits, parse_number};
+use reqwest::Method;
+use serde_json::Value;
 
-/// Box / Pin / Rc / Arc — فرق‌ها و کاربردها
+// Box / Pin / Rc / Arc — فرق‌ها و کاربردها
 // Box<T>
 //
 // چیست؟ مالکیت یکتا روی heap.
@@ -79,172 +85,250 @@ use crate::services::models::product::ProductCreate;
 //
 // Trait object لازم دارد پوینتر: Box<dyn Trait>, Rc<dyn Trait>, Arc<dyn Trait + Send + Sync>
 
-pub async fn create_product(
-    product: &crate::services::models::product::ProductCreate,
-    chat_id: String,
-) -> Result<u64, Box<dyn std::error::Error + Send + Sync + 'static>> {
+impl TorobClient {
+    /// ایجاد محصول؛ برمی‌گرداند: شناسهٔ محصول
+    pub async fn create_product(&self, product: &ProductCreate) -> TorobResult<u64> {
+        // فرم مولتی‌پارت طبق اسکیما: همهٔ فیلدهای مقداردار
+        let form = product_form_fields(product)
+            .into_iter()
+            .fold(reqwest::multipart::Form::new(), |form, (key, value)| {
+                form.text(key, value)
+            });
 
-    use reqwest::header::{ACCEPT, CONTENT_TYPE, CONTENT_TYPE as CT, ORIGIN, REFERER, USER_AGENT};
+        let body = self
+            .post_multipart("/api/management/v1/products/", form)
+            .await?;
 
-    let site = get_site(&chat_id).unwrap();
+        TorobClient::extract_id(&body).ok_or_else(|| {
+            TorobError::InvalidResponse(format!(
+                "product created but could not extract id. body: {}",
+                body
+            ))
+        })
+    }
 
-    let endpoint = format!("{}/api/management/v1/products/", site);
-    let referer = format!("{}/admin/",site);
-    let origin = site.trim_end_matches('/').to_string();
+    /// خواندن یک محصول با شناسه
+    pub async fn get_product(&self, id: u64) -> TorobResult<Product> {
+        let body = self
+            .get_json(&format!("/api/management/v1/products/{}/", id))
+            .await?;
+        parse_product(&body).map(|(product, _)| product)
+    }
 
-    let price_string =
-        product.price.expect("REASON").to_string();
+    /// مثل `get_product` ولی اگر فیلدی قابل خواندن نبود خطا می‌دهد؛
+    /// برای جاهایی که محصول باید عیناً دوباره ساخته شود (سطل بازیافت)
+    pub async fn get_complete_product(&self, id: u64) -> TorobResult<Product> {
+        let body = self
+            .get_json(&format!("/api/management/v1/products/{}/", id))
+            .await?;
+        match parse_product(&body)? {
+            (product, dropped) if dropped.is_empty() => Ok(product),
+            (_, dropped) => Err(TorobError::InvalidResponse(format!(
+                "product {} has unreadable fields: {}",
+                id,
+                dropped.join(", ")
+            ))),
+        }
+    }
 
-    let token =
-        crate::utilities::token::get_token(chat_id).expect("no token");
+    /// یک صفحه از لیست محصولات با فیلترهای جستجو، دسته‌بندی و فعال بودن
+    pub async fn list_products(&self, query: &ProductQuery) -> TorobResult<ProductPage> {
+        let mut url = reqwest::Url::parse(&self.url("/api/management/v1/products/"))
+            .map_err(|e| TorobError::InvalidUrl(e.to_string()))?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            pairs.append_pair("page", &query.page.max(1).to_string());
+            if let Some(search) = query.search.as_deref().filter(|s| !s.trim().is_empty()) {
+                pairs.append_pair("search", search.trim());
+            }
+            if let Some(category) = query.category {
+                pairs.append_pair("main_category", &category.to_string());
+            }
+            if let Some(available) = query.available {
+                pairs.append_pair("available", if available { "true" } else { "false" });
+            }
+        }
 
-    if token.is_empty() {
-        return Err("no token".into());
+        let page = self.get_page(url.as_str()).await?;
+        Ok(ProductPage {
+            items: page.items.iter().filter_map(value_to_product).collect(),
+            page: query.page.max(1),
+            total_pages: page.total_pages,
+            count: page.count,
+            has_next: page.next.is_some(),
+            has_previous: page.previous.is_some(),
+        })
     }
-    
-    // فرم مولتی‌پارت طبق اسکیما:
-    let form= reqwest::multipart::Form::new()
-        .text("name", product.name.to_string())
-        .text("price", price_string)
-        .text("main_category", product.main_category.to_string());
-
-    let client = reqwest::Client::new();
-
-    let resp = client
-        .post(endpoint)
-        // پاسخ JSON می‌خواهیم
-        .header(ACCEPT, "application/json")
-        .header(USER_AGENT, "reqwest")
-        .header(REFERER, &referer)
-        .header(ORIGIN, &origin)
-        // ApiKeyAuth در هدر Authorization
-        .header(reqwest::header::AUTHORIZATION, format!("Api-Key {}", token))
-        // بدنه مولتی‌پارت
-        .multipart(form)
-        .send()
-        .await?;
-
-    let status = resp.status();
-    let ct_hdr = resp
-        .headers()
-        .get(CT)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("")
-        .to_string();
-    let text = resp.text().await?;
-
-    if (status.is_success() || status.is_redirection()) == false {
-        let preview: String = text.chars().take(400).collect();
-        return Err(format!("product create failed: {} • {}", status, preview).into());
+
+    /// جستجوی محصولات (نام، بارکد، ...) — فقط صفحهٔ اول نتایج
+    pub async fn search_products(&self, search: &str) -> TorobResult<Vec<Product>> {
+        let query = ProductQuery {
+            search: Some(search.to_string()),
+            page: 1,
+            ..ProductQuery::default()
+        };
+        Ok(self.list_products(&query).await?.items)
     }
-    if ct_hdr.contains("application/json") == false && text.trim_start().starts_with('{') == false {
-        return Err(format!("unexpected content-type: {}", ct_hdr).into());
+
+    /// پیدا کردن محصول با شناسه، نام یا بارکد؛ تطابق دقیق بارکد/شناسه اول می‌آید
+    pub async fn find_products(&self, query: &str) -> TorobResult<Vec<Product>> {
+        let query = query.trim();
+        let mut found: Vec<Product> = Vec::new();
+
+        if let Some(id) = parse_number::<u64>(query) {
+            match self.get_product(id).await {
+                Ok(p) => found.push(p),
+                // عدد ممکن است بارکد باشد نه شناسه
+                Err(TorobError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        for p in self.search_products(query).await? {
+            if found.iter().all(|f| f.id != p.id) {
+                found.push(p);
+            }
+        }
+
+        let digits = normalize_digits(query);
+        found.sort_by_key(|p| p.fields.barcode.as_deref() != Some(digits.as_str()) && p.id.to_string() != digits);
+        Ok(found)
     }
 
-    #[derive(serde::Deserialize)]
-    struct Minimal {
-        id: u64,
+    /// تغییر جزئی محصول (PATCH)؛ برمی‌گرداند: محصول به‌روزشده
+    pub async fn update_product(&self, id: u64, patch: &Value) -> TorobResult<Product> {
+        let body = self
+            .send_json(
+                Method::PATCH,
+                &format!("/api/management/v1/products/{}/", id),
+                patch,
+            )
+            .await?;
+        match value_to_product(&body) {
+            Some(p) => Ok(p),
+            // برخی پاسخ‌ها فقط `{ "success": true }` هستند
+            None => self.get_product(id).await,
+        }
     }
-    if let Ok(min) = serde_json::from_str::<Minimal>(&text) {
-        return Ok(min.id);
+
+    /// حذف محصول
+    pub async fn delete_product(&self, id: u64) -> TorobResult<()> {
+        self.delete(&format!("/api/management/v1/products/{}/", id))
+            .await
     }
-    let v: serde_json::Value = serde_json::from_str(&text)?;
-    if let Some(id) = v.get("id").and_then(|x| x.as_u64()) {
-        return Ok(id);
+
+    /// ساخت دوبارهٔ یک محصول حذف‌شده با همان فیلدها و تصاویر؛
+    /// برمی‌گرداند: شناسهٔ جدید و تعداد تصاویری که دوباره آپلود شد
+    pub async fn restore_product(&self, deleted: &DeletedProduct) -> TorobResult<(u64, usize)> {
+        let new_id = self.create_product(&deleted.product.fields).await?;
+
+        let mut restored = 0;
+        for image in &deleted.images {
+            // تصویری که ثبت نشد نباید بازگردانی محصول را خراب کند
+            match self.create_product_image(image.to_create(new_id)).await {
+                Ok(_) => restored += 1,
+                Err(e) => eprintln!(
+                    "could not restore image {} of product {}: {}",
+                    image.filename, new_id, e
+                ),
+            }
+        }
+
+        Ok((new_id, restored))
     }
-    if let Some(id) = v
-        .get("result")
-        .and_then(|r| r.get("id"))
-        .and_then(|x| x.as_u64())
-    {
-        return Ok(id);
+}
+
+/// فیلدهای مقداردار `ProductCreate` به صورت جفت‌های فرم؛
+/// لیست‌ها (مثل `other_categories`) به صورت چند فیلد هم‌نام فرستاده می‌شوند.
+pub fn product_form_fields(product: &ProductCreate) -> Vec<(String, String)> {
+    let Ok(Value::Object(map)) = serde_json::to_value(product) else {
+        return Vec::new();
+    };
+
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for (key, value) in map {
+        match value {
+            Value::Null => {}
+            Value::Array(items) => {
+                for item in items {
+                    fields.push((key.clone(), form_value(&item)));
+                }
+            }
+            other => fields.push((key, form_value(&other))),
+        }
     }
+    fields
+}
 
-    Err(format!("product created but could not extract id. body: {}", text).into())
+fn form_value(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// محصول از `{...}` یا `{ "result": {...} }` همراه با کلید فیلدهای کنارگذاشته
+fn parse_product(body: &Value) -> TorobResult<(Product, Vec<String>)> {
+    read_product(body)
+        .or_else(|| body.get("result").and_then(read_product))
+        .ok_or_else(|| {
+            TorobError::InvalidResponse(format!(
+                "could not read product. body: {}",
+                body.to_string().chars().take(400).collect::<String>()
+            ))
+        })
 }
 
-pub async fn create_product_with_custom_auth()
-    -> Result<u64, Box<dyn std::error::Error + Send + Sync + 'static>> {
-    use reqwest::header::{ACCEPT, CONTENT_TYPE, CONTENT_TYPE as CT, ORIGIN, REFERER, USER_AGENT};
+pub async fn create_product(product: &ProductCreate, chat_id: String) -> TorobResult<u64> {
+    TorobClient::for_chat(&chat_id)?.create_product(product).await
+}
 
+pub async fn create_product_with_custom_auth() -> TorobResult<u64> {
     let product: ProductCreate = ProductCreate::new("تست محصول جدید", 72);
 
     let token = "VpexCfgM3FtomndUjzCtyT0_qSfRzjEEXWhRdPQpu7pHuSr_eDBQGKah9mj5oB92";
 
-    let endpoint = String::from("https://testmixin.ir/api/management/v1/products/");
-    let referer = String::from("https://testmixin.ir/admin/");
-    let origin = String::from("https://testmixin.ir/");
-
-    let http_client = reqwest::Client::new();
-
-    // فرم مولتی‌پارت طبق اسکیما:
-    let form = reqwest::multipart::Form::new()
-        .text("name", product.name.to_string())
-        .text("main_category", product.main_category.to_string());
-
-    let client = reqwest::Client::new();
-    let resp = client
-        .post(endpoint)
-        // پاسخ JSON می‌خواهیم
-        .header(ACCEPT, "application/json")
-        .header(USER_AGENT, "reqwest")
-        .header(REFERER, &referer)
-        .header(ORIGIN, &origin)
-        // ApiKeyAuth در هدر Authorization
-        .header(reqwest::header::AUTHORIZATION, format!("Api-Key {}", token))
-        // بدنه مولتی‌پارت
-        .multipart(form)
-        .send()
-        .await?;
-
-    let status = resp.status();
-    let ct_hdr = resp
-        .headers()
-        .get(CT)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("")
-        .to_string();
-    let text = resp.text().await?;
-
-    if (status.is_success() || status.is_redirection()) == false {
-        let preview: String = text.chars().take(400).collect();
-        return Err(format!("product create failed: {} • {}", status, preview).into());
-    }
-    if ct_hdr.contains("application/json") == false && text.trim_start().starts_with('{') == false {
-        return Err(format!("unexpected content-type: {}", ct_hdr).into());
-    }
-
-    #[derive(serde::Deserialize)]
-    struct Minimal {
-        id: u64,
-    }
-    if let Ok(min) = serde_json::from_str::<Minimal>(&text) {
-        return Ok(min.id);
-    }
-    let v: serde_json::Value = serde_json::from_str(&text)?;
-    if let Some(id) = v.get("id").and_then(|x| x.as_u64()) {
-        return Ok(id);
-    }
-    if let Some(id) = v
-        .get("result")
-        .and_then(|r| r.get("id"))
-        .and_then(|x| x.as_u64())
-    {
-        return Ok(id);
-    }
-
-    Err(format!("product created but could not extract id. body: {}", text).into())
+    TorobClient::new("https://testmixin.ir", token)?
+        .create_product(&product)
+        .await
 }
 
 #[cfg(test)]
 mod test_create_product {
-    use std::error::Error;
     use super::*;
+    use crate::services::models::product::StockType;
     use tokio;
 
+    #[test]
+    fn test_form_fields_include_every_populated_field() {
+        let mut product = ProductCreate::new("کفش", 7);
+        product.price = Some(250000);
+        product.is_digital = Some(false);
+        product.stock_type = Some(StockType::OutOfStock);
+        product.other_categories = Some(vec![3, 4]);
+        product.english_name = Some("Shoe".into());
+
+        let fields = product_form_fields(&product);
+        let get = |k: &str| -> Vec<&str> {
+            fields
+                .iter()
+                .filter(|(key, _)| key == k)
+                .map(|(_, v)| v.as_str())
+                .collect()
+        };
+        assert_eq!(get("name"), vec!["کفش"]);
+        assert_eq!(get("main_category"), vec!["7"]);
+        assert_eq!(get("price"), vec!["250000"]);
+        assert_eq!(get("is_digital"), vec!["false"]);
+        assert_eq!(get("stock_type"), vec!["out_of_stock"]);
+        assert_eq!(get("other_categories"), vec!["3", "4"]);
+        assert_eq!(get("english_name"), vec!["Shoe"]);
+        assert!(get("description").is_empty());
+    }
+
     #[tokio::test]
     async fn test_create_product_success() {
-        let result: Result<u64, Box<dyn Error + Send + Sync>> =
+        let result: Result<u64, TorobError> =
             create_product_with_custom_auth().await;
         assert!(result.is_ok());
     }
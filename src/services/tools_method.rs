@@ -1,5 +1,7 @@
-use serde_json::Value;
+use serde_json::{json, Value};
 use crate::services::models::category::Category;
+use crate::services::models::product::{Product, ProductCreate};
+use crate::services::models::product_image::ProductImage;
 
 pub fn val_to_opt_u64(v: &Value) -> Option<u64> {
     match v {
@@ -40,4 +42,168 @@ pub fn value_to_category(v: &Value) -> Option<Category> {
         parent,
         available,
     })
-}
\ No newline at end of file
+}
+/// فیلدهای عددی `ProductCreate` که API ممکن است به صورت رشته یا `12.00` برگرداند
+const PRODUCT_NUMBER_KEYS: &[&str] = &[
+    "price",
+    "compare_at_price",
+    "length",
+    "width",
+    "height",
+    "weight",
+    "stock",
+    "max_order_quantity",
+];
+
+/// عدد صحیح نامنفی بدون از دست رفتن دقت؛ `"12"` و `"12.00"` قبول، `"12.5"` و `"-3"` رد می‌شوند
+fn exact_u64(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if !frac.chars().all(|c| c == '0') {
+        return None;
+    }
+    int.parse::<u64>().ok()
+}
+
+/// تبدیل یک محصول از پاسخ API به `Product`؛ شیءهای تودرتو (مثل `main_category: {id, ...}`) به شناسه تبدیل می‌شوند
+pub fn value_to_product(v: &Value) -> Option<Product> {
+    read_product(v).map(|(product, _)| product)
+}
+
+/// مثل `value_to_product` ولی کلید فیلدهایی را هم که قابل خواندن نبودند و کنار گذاشته شدند برمی‌گرداند.
+/// بدون شناسه، نام یا دستهٔ اصلی محصولی ساخته نمی‌شود.
+pub fn read_product(v: &Value) -> Option<(Product, Vec<String>)> {
+    let id = v.get("id").and_then(val_to_opt_u64)?;
+    let obj = v.as_object()?;
+
+    let as_id = |x: &Value| x.get("id").and_then(val_to_opt_u64).or_else(|| val_to_opt_u64(x));
+    let Some(name) = obj.get("name").and_then(|x| x.as_str()) else {
+        eprintln!("product {} has no readable name; skipped", id);
+        return None;
+    };
+    let Some(main_category) = obj.get("main_category").and_then(as_id) else {
+        eprintln!("product {} has no readable main_category; skipped", id);
+        return None;
+    };
+    let required = json!({ "name": name, "main_category": main_category });
+
+    let mut map = required.as_object().cloned().unwrap_or_default();
+    let mut dropped: Vec<String> = Vec::new();
+    for (key, value) in obj {
+        let value = match key.as_str() {
+            "id" | "name" | "main_category" => continue,
+            "brand" => match value {
+                Value::Null => Value::Null,
+                other => match as_id(other) {
+                    Some(id) => Value::from(id),
+                    None => {
+                        dropped.push(key.clone());
+                        continue;
+                    }
+                },
+            },
+            "other_categories" => {
+                let ids = value
+                    .as_array()
+                    .map(|items| items.iter().map(as_id).collect::<Option<Vec<u64>>>());
+                match ids {
+                    Some(Some(ids)) => Value::from(ids),
+                    _ if value.is_null() => Value::Null,
+                    _ => {
+                        dropped.push(key.clone());
+                        continue;
+                    }
+                }
+            }
+            k if PRODUCT_NUMBER_KEYS.contains(&k) => {
+                let number = match value {
+                    Value::Null => Some(Value::Null),
+                    Value::String(s) => exact_u64(s).map(Value::from),
+                    Value::Number(n) => exact_u64(&n.to_string()).map(Value::from),
+                    _ => None,
+                };
+                match number {
+                    Some(n) => n,
+                    None => {
+                        dropped.push(key.clone());
+                        continue;
+                    }
+                }
+            }
+            _ => value.clone(),
+        };
+
+        // هر فیلد جداگانه سنجیده می‌شود تا خطای یک فیلد بقیه را از بین نبرد
+        let mut probe = required.clone();
+        probe[key.as_str()] = value.clone();
+        if serde_json::from_value::<ProductCreate>(probe).is_err() {
+            dropped.push(key.clone());
+            continue;
+        }
+        map.insert(key.clone(), value);
+    }
+
+    for key in &dropped {
+        eprintln!("product {}: field {} = {} could not be read; dropped", id, key, obj[key]);
+    }
+    match serde_json::from_value::<ProductCreate>(Value::Object(map)) {
+        Ok(fields) => Some((Product { id, fields }, dropped)),
+        Err(e) => {
+            eprintln!("product {} could not be read: {}", id, e);
+            None
+        }
+    }
+}
+
+/// تبدیل یک تصویر محصول از پاسخ API به `ProductImage`
+pub fn value_to_product_image(v: &Value) -> Option<ProductImage> {
+    let id = v.get("id").and_then(val_to_opt_u64)?;
+    let text = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|k| v.get(*k).and_then(|x| x.as_str()))
+            .find(|s| !s.trim().is_empty())
+            .map(|s| s.to_string())
+    };
+    Some(ProductImage {
+        id,
+        image: text(&["image", "image_url", "url"]),
+        image_alt: text(&["image_alt", "alt"]),
+        default: v
+            .get("default")
+            .or_else(|| v.get("is_default"))
+            .map(|x| val_to_bool_default(x, false))
+            .unwrap_or(false),
+    })
+}
+
+#[cfg(test)]
+mod test_tools_method {
+    use super::*;
+
+    #[test]
+    fn test_read_product_drops_only_bad_fields() {
+        let body = json!({
+            "id": 9,
+            "name": "کفش",
+            "main_category": { "id": 7, "name": "پوشاک" },
+            "price": "250000.00",
+            "stock": "12.5",
+            "weight": 2.0,
+            "length": 5000000000u64,
+            "barcode": "123",
+            "stock_type": "unknown",
+            "slug": "shoe"
+        });
+        let (product, mut dropped) = read_product(&body).unwrap();
+        dropped.sort();
+        assert_eq!(dropped, vec!["length", "stock", "stock_type"]);
+        assert_eq!(product.fields.main_category, 7);
+        assert_eq!(product.fields.price, Some(250000));
+        assert_eq!(product.fields.weight, Some(2));
+        assert_eq!(product.fields.barcode.as_deref(), Some("123"));
+        assert_eq!(product.fields.stock, None);
+
+        // بدون دستهٔ اصلی محصولی با دستهٔ 0 ساخته نمی‌شود
+        assert!(value_to_product(&json!({ "id": 9, "name": "کفش" })).is_none());
+    }
+}
@@ -0,0 +1,303 @@
+use crate::services::torob_error::{TorobError, TorobResult};
+use crate::utilities::config::http_client_builder;
+use crate::utilities::site::get_site;
+use crate::utilities::token::get_token;
+use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ORIGIN, REFERER, RETRY_AFTER, USER_AGENT};
+use reqwest::multipart::Form;
+use reqwest::{Method, RequestBuilder, Response};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// سقف صفحاتی که `get_paginated` دنبال می‌کند
+const MAX_PAGES: usize = 1000;
+
+/// کلاینت HTTP مشترک (connection pool) برای همهٔ درخواست‌ها
+static HTTP: OnceLock<reqwest::Client> = OnceLock::new();
+
+pub(crate) fn shared_http() -> TorobResult<reqwest::Client> {
+    if let Some(c) = HTTP.get() {
+        return Ok(c.clone());
+    }
+    let client = http_client_builder().build()?;
+    Ok(HTTP.get_or_init(|| client).clone())
+}
+
+/// ====== کلاینت API مدیریت فروشگاه‌های ترب ======
+///
+/// آدرس پایه، هدرهای Referer/Origin/User-Agent، احراز هویت `Api-Key`،
+/// بررسی نوع پاسخ و استخراج شناسه را یک‌جا انجام می‌دهد.
+///
+/// ```no_run
+/// # async fn demo() -> Result<(), telegram_bot_torob::TorobError> {
+/// use telegram_bot_torob::TorobClient;
+///
+/// let client = TorobClient::new("https://shop.example.ir", "my-api-key")?;
+/// let categories = client.fetch_categories().await?;
+/// # Ok(()) }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TorobClient {
+    http: reqwest::Client,
+    base: String,
+    token: String,
+}
+
+impl TorobClient {
+    /// ساخت کلاینت از آدرس پنل و Api-Key
+    pub fn new(site: impl Into<String>, token: impl Into<String>) -> TorobResult<Self> {
+        let site = site.into();
+        let token = token.into();
+        if site.trim().is_empty() {
+            return Err(TorobError::MissingSite);
+        }
+        if token.trim().is_empty() {
+            return Err(TorobError::MissingToken);
+        }
+        Ok(Self {
+            http: shared_http()?,
+            base: site.trim().trim_end_matches('/').to_string(),
+            token: token.trim().to_string(),
+        })
+    }
+
+    /// ساخت کلاینت از اطلاعات ذخیره‌شدهٔ یک چت
+    pub fn for_chat(chat_id: &str) -> TorobResult<Self> {
+        let site = get_site(chat_id).ok_or(TorobError::MissingSite)?;
+        let token = get_token(chat_id).ok_or(TorobError::MissingToken)?;
+        Self::new(site, token)
+    }
+
+    /// آدرس پایهٔ پنل (بدون `/` انتهایی)
+    pub fn base_url(&self) -> &str {
+        &self.base
+    }
+
+    /// مسیر نسبی (`/api/...`) یا آدرس کامل را به آدرس کامل تبدیل می‌کند
+    pub fn url(&self, path_or_url: &str) -> String {
+        if path_or_url.starts_with("https://") || path_or_url.starts_with("http://") {
+            path_or_url.to_string()
+        } else if path_or_url.starts_with('/') {
+            format!("{}{}", self.base, path_or_url)
+        } else {
+            format!("{}/{}", self.base, path_or_url)
+        }
+    }
+
+    /// آیا آدرس کامل روی همان میزبان (و پورت) پنل است
+    pub fn is_panel_url(&self, url: &str) -> bool {
+        let (Ok(base), Ok(url)) = (reqwest::Url::parse(&self.base), reqwest::Url::parse(url)) else {
+            return false;
+        };
+        base.host_str().is_some()
+            && base.host_str() == url.host_str()
+            && base.port_or_known_default() == url.port_or_known_default()
+    }
+
+    /// درخواست با همهٔ هدرهای لازم؛ Api-Key فقط به میزبان خود پنل فرستاده می‌شود
+    /// (آدرس کامل ممکن است از پاسخ سرور آمده باشد، مثل `next`)
+    pub fn request(&self, method: Method, path_or_url: &str) -> TorobResult<RequestBuilder> {
+        let url = self.url(path_or_url);
+        if !self.is_panel_url(&url) {
+            return Err(TorobError::InvalidResponse(format!(
+                "refusing to send the Api-Key to {}: not the panel host",
+                url
+            )));
+        }
+        Ok(self
+            .http
+            .request(method, url)
+            .header(ACCEPT, "application/json")
+            .header(USER_AGENT, "reqwest")
+            .header(REFERER, format!("{}/admin/", self.base))
+            .header(ORIGIN, &self.base)
+            // ApiKeyAuth در هدر Authorization
+            .header(AUTHORIZATION, format!("Api-Key {}", self.token)))
+    }
+
+    /// GET و برگرداندن JSON
+    pub async fn get_json(&self, path_or_url: &str) -> TorobResult<Value> {
+        let resp = self.request(Method::GET, path_or_url)?.send().await?;
+        Self::read_json(resp).await
+    }
+
+    /// POST مولتی‌پارت و برگرداندن JSON
+    pub async fn post_multipart(&self, path_or_url: &str, form: Form) -> TorobResult<Value> {
+        let resp = self
+            .request(Method::POST, path_or_url)?
+            .multipart(form)
+            .send()
+            .await?;
+        Self::read_json(resp).await
+    }
+
+    /// DELETE؛ پاسخ خالی (204) هم موفق است
+    pub async fn delete(&self, path_or_url: &str) -> TorobResult<()> {
+        let resp = self.request(Method::DELETE, path_or_url)?.send().await?;
+        Self::read_json(resp).await?;
+        Ok(())
+    }
+
+    /// ارسال بدنهٔ JSON (مثلاً PATCH) و برگرداندن JSON
+    pub async fn send_json(&self, method: Method, path_or_url: &str, body: &Value) -> TorobResult<Value> {
+        let resp = self.request(method, path_or_url)?.json(body).send().await?;
+        Self::read_json(resp).await
+    }
+
+    /// خواندن یک صفحه از یک لیست صفحه‌بندی‌شده
+    pub async fn get_page(&self, path_or_url: &str) -> TorobResult<Page> {
+        let root = self.get_json(path_or_url).await?;
+        let Some(items) = page_items(&root) else {
+            let preview = root.to_string();
+            return Err(TorobError::InvalidResponse(format!(
+                "no results/result array. preview: {}",
+                preview.chars().take(400).collect::<String>()
+            )));
+        };
+
+        let link = |key: &str| {
+            root.get(key)
+                .and_then(|x| x.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| self.url(s))
+        };
+        Ok(Page {
+            items: items.clone(),
+            next: link("next"),
+            previous: link("previous"),
+            count: root.get("count").and_then(|x| x.as_u64()),
+            total_pages: root
+                .get("total_pages")
+                .and_then(|x| x.as_u64())
+                .map(|n| n as u32),
+        })
+    }
+
+    /// همهٔ صفحات یک لیست را با دنبال کردن `next` می‌خواند و آیتم‌ها را پشت هم برمی‌گرداند
+    pub async fn get_paginated(&self, path_or_url: &str) -> TorobResult<Vec<Value>> {
+        let mut url = self.url(path_or_url);
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut out: Vec<Value> = Vec::new();
+
+        loop {
+            if visited.len() >= MAX_PAGES {
+                return Err(TorobError::InvalidResponse(format!(
+                    "more than {} pages while reading {}",
+                    MAX_PAGES, path_or_url
+                )));
+            }
+            visited.insert(url.to_ascii_lowercase());
+            let page = self.get_page(&url).await?;
+            out.extend(page.items);
+
+            // اگر next خالی بود یا به صفحه‌ای که خوانده‌ایم برگشت (حلقه)، تمام است
+            match page.next {
+                Some(next) if visited.contains(&next.to_ascii_lowercase()) => break,
+                Some(next) if !self.is_panel_url(&next) => {
+                    return Err(TorobError::InvalidResponse(format!(
+                        "next page {} is not on the panel host",
+                        next
+                    )));
+                }
+                Some(next) => url = next,
+                None => break,
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// بررسی وضعیت و نوع محتوای پاسخ و تبدیل بدنه به JSON
+    pub async fn read_json(resp: Response) -> TorobResult<Value> {
+        let status = resp.status();
+        let retry_after: Option<u64> = resp
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.trim().parse().ok());
+        let ct: String = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+        let text = resp.text().await?;
+
+        if !(status.is_success() || status.is_redirection()) {
+            return Err(TorobError::from_response(status, retry_after, &text));
+        }
+
+        let trimmed = text.trim_start();
+        // مثلاً 204 بعد از DELETE
+        if trimmed.is_empty() {
+            return Ok(Value::Null);
+        }
+
+        if !ct.contains("application/json") && !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+            return Err(TorobError::UnexpectedContentType {
+                content_type: ct,
+                preview: text.chars().take(400).collect(),
+            });
+        }
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// استخراج شناسه از پاسخ‌هایی مثل `{ "id": 2 }` یا `{ "result": { "id": 2 } }`
+    pub fn extract_id(v: &Value) -> Option<u64> {
+        v.get("id")
+            .and_then(|x| x.as_u64())
+            .or_else(|| v.get("result").and_then(|r| r.get("id")).and_then(|x| x.as_u64()))
+    }
+}
+
+/// یک صفحه از لیست‌های صفحه‌بندی‌شدهٔ API
+#[derive(Debug, Clone)]
+pub struct Page {
+    pub items: Vec<Value>,
+    /// آدرس کامل صفحهٔ بعد/قبل (در صورت وجود)
+    pub next: Option<String>,
+    pub previous: Option<String>,
+    /// تعداد کل آیتم‌ها، اگر API برگرداند
+    pub count: Option<u64>,
+    pub total_pages: Option<u32>,
+}
+
+/// آرایهٔ آیتم‌های یک صفحه (`results`، `result` یا خود آرایه)
+pub fn page_items(root: &Value) -> Option<&Vec<Value>> {
+    root.get("results")
+        .and_then(|v| v.as_array())
+        .or_else(|| root.get("result").and_then(|v| v.as_array()))
+        .or_else(|| root.as_array())
+}
+
+#[cfg(test)]
+mod test_torob_client {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_url_and_id_helpers() {
+        let client = TorobClient::new("https://shop.ir/", "key").unwrap();
+        assert_eq!(client.base_url(), "https://shop.ir");
+        assert_eq!(
+            client.url("/api/management/v1/categories/?page=2"),
+            "https://shop.ir/api/management/v1/categories/?page=2"
+        );
+        assert_eq!(client.url("https://cdn.shop.ir/x"), "https://cdn.shop.ir/x");
+        assert!(client.is_panel_url(&client.url("/media/a.jpg")));
+        assert!(!client.is_panel_url("https://cdn.shop.ir/x"));
+        assert!(!client.is_panel_url("http://169.254.169.254/latest"));
+        assert!(!client.is_panel_url("https://shop.ir:8443/media/a.jpg"));
+        // Api-Key به میزبان دیگری فرستاده نمی‌شود
+        assert!(client.request(Method::GET, "/api/management/v1/products/").is_ok());
+        assert!(client.request(Method::GET, "https://evil.ir/api/").is_err());
+
+        assert_eq!(TorobClient::extract_id(&json!({"id": 5})), Some(5));
+        assert_eq!(TorobClient::extract_id(&json!({"result": {"id": 6}})), Some(6));
+        assert_eq!(TorobClient::extract_id(&json!({"success": true})), None);
+        assert!(matches!(
+            TorobClient::new("https://shop.ir", " "),
+            Err(TorobError::MissingToken)
+        ));
+    }
+}
@@ -0,0 +1,289 @@
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::fmt;
+
+/// ====== خطاهای API مدیریت ترب ======
+///
+/// هر خطا علاوه بر متن انگلیسی برای لاگ، یک پیام فارسی قابل نمایش به کاربر دارد (`user_message`).
+#[derive(Debug)]
+pub enum TorobError {
+    /// آدرس پنل برای این چت ثبت نشده
+    MissingSite,
+    /// Api-Key برای این چت ثبت نشده
+    MissingToken,
+    /// آدرس واردشده قابل تبدیل به یک آدرس https معتبر نیست
+    InvalidUrl(String),
+    /// میزبان در دسترس نیست (DNS/اتصال/زمان انتظار)
+    Unreachable(String),
+    /// میزبان پاسخ داد ولی API مدیریت ترب ندارد
+    NotTorobShop { status: u16 },
+    /// کلید نامعتبر است یا دسترسی ندارد (401/403)
+    Unauthorized { status: u16 },
+    /// داده‌های ارسالی رد شد (400/422)؛ پیام‌ها به تفکیک فیلد
+    Validation {
+        fields: Vec<(String, Vec<String>)>,
+        detail: Option<String>,
+    },
+    /// مورد درخواستی پیدا نشد (404)
+    NotFound,
+    /// محدودیت تعداد درخواست (429)
+    RateLimited { retry_after: Option<u64> },
+    /// خطای داخلی سرور (5xx)
+    Server { status: u16, preview: String },
+    /// سایر وضعیت‌های ناموفق
+    UnexpectedStatus { status: u16, preview: String },
+    /// پاسخ JSON نبود (مثلاً صفحهٔ HTML)
+    UnexpectedContentType { content_type: String, preview: String },
+    /// JSON بود ولی ساختارش قابل استفاده نبود
+    InvalidResponse(String),
+    /// خطای شبکه/اتصال/زمان انتظار
+    Network(reqwest::Error),
+}
+
+pub type TorobResult<T> = Result<T, TorobError>;
+
+impl fmt::Display for TorobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TorobError::MissingSite => write!(f, "no site"),
+            TorobError::InvalidUrl(why) => write!(f, "invalid panel url: {}", why),
+            TorobError::Unreachable(why) => write!(f, "panel unreachable: {}", why),
+            TorobError::NotTorobShop { status } => {
+                write!(f, "not a Torob-powered shop (management API answered {})", status)
+            }
+            TorobError::MissingToken => write!(f, "no token"),
+            TorobError::Unauthorized { status } => write!(f, "unauthorized ({})", status),
+            TorobError::Validation { fields, detail } => {
+                write!(f, "validation failed")?;
+                if let Some(d) = detail {
+                    write!(f, ": {}", d)?;
+                }
+                for (field, messages) in fields {
+                    write!(f, "; {}: {}", field, messages.join(" "))?;
+                }
+                Ok(())
+            }
+            TorobError::NotFound => write!(f, "not found"),
+            TorobError::RateLimited { retry_after } => match retry_after {
+                Some(s) => write!(f, "rate limited (retry after {}s)", s),
+                None => write!(f, "rate limited"),
+            },
+            TorobError::Server { status, preview } => {
+                write!(f, "server error: {} • {}", status, preview)
+            }
+            TorobError::UnexpectedStatus { status, preview } => {
+                write!(f, "request failed: {} • {}", status, preview)
+            }
+            TorobError::UnexpectedContentType {
+                content_type,
+                preview,
+            } => write!(
+                f,
+                "unexpected content-type/body ({}). preview: {}",
+                content_type, preview
+            ),
+            TorobError::InvalidResponse(why) => write!(f, "invalid response: {}", why),
+            TorobError::Network(e) => write!(f, "network error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TorobError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TorobError::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for TorobError {
+    fn from(e: reqwest::Error) -> Self {
+        TorobError::Network(e)
+    }
+}
+
+impl From<serde_json::Error> for TorobError {
+    fn from(e: serde_json::Error) -> Self {
+        TorobError::InvalidResponse(e.to_string())
+    }
+}
+
+impl TorobError {
+    /// ساخت خطا از وضعیت و بدنهٔ یک پاسخ ناموفق
+    pub fn from_response(status: StatusCode, retry_after: Option<u64>, body: &str) -> Self {
+        let preview: String = body.chars().take(400).collect();
+        match status.as_u16() {
+            401 | 403 => TorobError::Unauthorized {
+                status: status.as_u16(),
+            },
+            400 | 422 => parse_validation(body),
+            404 => TorobError::NotFound,
+            429 => TorobError::RateLimited { retry_after },
+            s if status.is_server_error() => TorobError::Server { status: s, preview },
+            s => TorobError::UnexpectedStatus { status: s, preview },
+        }
+    }
+
+    /// پیام فارسی مناسب برای ارسال به کاربر ربات
+    pub fn user_message(&self) -> String {
+        match self {
+            TorobError::MissingSite => {
+                "آدرس پنل شما ثبت نشده است؛ با /changetoken آدرس پنل و توکن را وارد کنید.".into()
+            }
+            TorobError::MissingToken => {
+                "توکن (Api-Key) شما ثبت نشده است؛ با /changetoken آن را وارد کنید.".into()
+            }
+            TorobError::InvalidUrl(_) => {
+                "آدرس واردشده معتبر نیست؛ آدرس را به شکل https://example.ir بفرستید.".into()
+            }
+            TorobError::Unreachable(_) => {
+                "سایت در دسترس نیست؛ آدرس را بررسی کنید یا کمی بعد دوباره تلاش کنید.".into()
+            }
+            TorobError::NotTorobShop { .. } => {
+                "این آدرس پاسخ داد ولی پنل فروشگاه‌ساز ترب روی آن پیدا نشد؛ آدرس پنل را بررسی کنید."
+                    .into()
+            }
+            TorobError::Unauthorized { .. } => {
+                "توکن شما نامعتبر است یا دسترسی لازم را ندارد؛ با /changetoken توکن جدید وارد کنید."
+                    .into()
+            }
+            TorobError::Validation { fields, detail } => {
+                let mut lines = vec!["اطلاعات ارسالی توسط سایت پذیرفته نشد:".to_string()];
+                if let Some(d) = detail {
+                    lines.push(format!("• {}", d));
+                }
+                for (field, messages) in fields {
+                    lines.push(format!("• {}: {}", field_label(field), messages.join(" ")));
+                }
+                lines.join("\n")
+            }
+            TorobError::NotFound => "مورد درخواستی در سایت پیدا نشد.".into(),
+            TorobError::RateLimited { retry_after } => match retry_after {
+                Some(s) => format!(
+                    "تعداد درخواست‌ها زیاد است؛ لطفاً {} ثانیهٔ دیگر دوباره تلاش کنید.",
+                    s
+                ),
+                None => "تعداد درخواست‌ها زیاد است؛ لطفاً کمی بعد دوباره تلاش کنید.".into(),
+            },
+            TorobError::Server { status, .. } => format!(
+                "سایت شما با خطای داخلی ({}) پاسخ داد؛ کمی بعد دوباره تلاش کنید.",
+                status
+            ),
+            TorobError::UnexpectedStatus { status, .. } => {
+                format!("پاسخ غیرمنتظره از سایت (کد {}).", status)
+            }
+            TorobError::UnexpectedContentType { .. } => {
+                "پاسخ سایت قابل فهم نبود؛ مطمئن شوید آدرس پنل درست است.".into()
+            }
+            TorobError::InvalidResponse(_) => "پاسخ سایت قابل فهم نبود.".into(),
+            TorobError::Network(e) if e.is_timeout() => {
+                "سایت شما در زمان مقرر پاسخ نداد؛ کمی بعد دوباره تلاش کنید.".into()
+            }
+            TorobError::Network(e) if e.is_connect() => {
+                "اتصال به سایت شما برقرار نشد؛ آدرس پنل را بررسی کنید.".into()
+            }
+            TorobError::Network(_) => "خطای شبکه در ارتباط با سایت شما.".into(),
+        }
+    }
+}
+
+/// خواندن خطاهای اعتبارسنجی به شکل‌های رایج:
+/// `{"name": ["..."]}`، `{"detail": "..."}`، `{"errors": {...}}`، `{"non_field_errors": [...]}`
+fn parse_validation(body: &str) -> TorobError {
+    let mut fields: Vec<(String, Vec<String>)> = Vec::new();
+    let mut detail: Option<String> = None;
+
+    let root: Value = serde_json::from_str(body).unwrap_or(Value::Null);
+    let obj = root
+        .get("errors")
+        .and_then(|v| v.as_object())
+        .or_else(|| root.as_object());
+
+    match obj {
+        Some(map) => {
+            for (key, value) in map {
+                let messages = collect_messages(value);
+                if messages.is_empty() {
+                    continue;
+                }
+                match key.as_str() {
+                    "detail" | "message" | "non_field_errors" | "errors" => {
+                        detail = Some(messages.join(" "));
+                    }
+                    "success" | "status" | "code" => {}
+                    _ => fields.push((key.clone(), messages)),
+                }
+            }
+        }
+        None => {
+            let text = body.trim();
+            if !text.is_empty() {
+                detail = Some(text.chars().take(400).collect());
+            }
+        }
+    }
+
+    TorobError::Validation { fields, detail }
+}
+
+fn collect_messages(v: &Value) -> Vec<String> {
+    match v {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().flat_map(collect_messages).collect(),
+        Value::Object(map) => map
+            .iter()
+            .flat_map(|(k, v)| {
+                collect_messages(v)
+                    .into_iter()
+                    .map(move |m| format!("{}: {}", k, m))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// نام فارسی فیلدهای رایج برای پیام‌های خطا
+fn field_label(field: &str) -> &str {
+    match field {
+        "name" => "نام",
+        "price" => "قیمت",
+        "main_category" => "دسته‌بندی",
+        "image" => "تصویر",
+        "image_url" => "لینک تصویر",
+        _ => field,
+    }
+}
+
+#[cfg(test)]
+mod test_torob_error {
+    use super::*;
+
+    #[test]
+    fn test_status_mapping_and_field_messages() {
+        let body = r#"{"name": ["This field is required."], "price": ["A valid integer is required."]}"#;
+        let err = TorobError::from_response(StatusCode::BAD_REQUEST, None, body);
+        match &err {
+            TorobError::Validation { fields, detail } => {
+                assert_eq!(fields.len(), 2);
+                assert_eq!(fields[0].0, "name");
+                assert!(detail.is_none());
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(err.user_message().contains("• نام: This field is required."));
+
+        assert!(matches!(
+            TorobError::from_response(StatusCode::FORBIDDEN, None, "{}"),
+            TorobError::Unauthorized { status: 403 }
+        ));
+        assert!(matches!(
+            TorobError::from_response(StatusCode::TOO_MANY_REQUESTS, Some(30), ""),
+            TorobError::RateLimited { retry_after: Some(30) }
+        ));
+        assert!(matches!(
+            TorobError::from_response(StatusCode::BAD_GATEWAY, None, "<html>"),
+            TorobError::Server { status: 502, .. }
+        ));
+    }
+}
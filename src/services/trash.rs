@@ -0,0 +1,294 @@
+use crate::services::models::product::Product;
+use crate::services::models::product_image::{ImageFile, ProductImage, ProductImageCreate};
+use crate::utilities::config::config;
+use crate::utilities::database::{database, SharedConnection};
+use rusqlite::{OptionalExtension, params};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub type TrashResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+/// تصویر یک محصول حذف‌شده؛ بایت‌ها قبل از حذف دانلود می‌شوند چون بعد از آن فایل روی سایت نمی‌ماند
+#[derive(Debug, Clone)]
+pub struct DeletedImage {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+    pub image_alt: Option<String>,
+    pub default: bool,
+}
+
+impl DeletedImage {
+    /// نام فایل از انتهای آدرس تصویر روی سایت
+    pub fn new(image: &ProductImage, bytes: Vec<u8>) -> Self {
+        let filename = image
+            .image
+            .as_deref()
+            .and_then(|u| u.split('?').next())
+            .and_then(|u| u.rsplit('/').next())
+            .filter(|f| !f.is_empty())
+            .unwrap_or("image.jpg")
+            .to_string();
+        Self {
+            filename,
+            bytes,
+            image_alt: image.image_alt.clone(),
+            default: image.default,
+        }
+    }
+
+    /// payload ثبت دوبارهٔ همین تصویر روی محصول `product_id`
+    pub fn to_create(&self, product_id: u64) -> ProductImageCreate {
+        ProductImageCreate {
+            pk: product_id,
+            image: Some(ImageFile {
+                filename: self.filename.clone(),
+                bytes: self.bytes.clone(),
+                mime: None,
+            }),
+            image_url: None,
+            image_alt: self.image_alt.clone(),
+            default: Some(self.default),
+        }
+    }
+}
+
+/// ====== سطل بازیافت محصولات حذف‌شده ======
+///
+/// اطلاعات محصول حذف‌شده و تصاویرش در جداول `trash` و `trash_images` برای مدت کوتاهی
+/// (`trash.ttl_secs`) نگه داشته می‌شود تا بتوان آن را دوباره ساخت، حتی بعد از ری‌استارت.
+#[derive(Debug, Clone)]
+pub struct DeletedProduct {
+    pub product: Product,
+    pub images: Vec<DeletedImage>,
+    deleted_at: i64,
+}
+
+pub struct Trash {
+    ttl: Duration,
+    conn: SharedConnection,
+}
+
+impl Trash {
+    pub fn new(conn: SharedConnection, ttl: Duration) -> Self {
+        Self { ttl, conn }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// افزودن محصول حذف‌شدهٔ یک چت (موارد منقضی همان چت پاک می‌شوند)
+    pub fn put(&self, chat_id: i64, product: Product, images: Vec<DeletedImage>) -> TrashResult<()> {
+        self.insert(
+            chat_id,
+            &DeletedProduct {
+                product,
+                images,
+                deleted_at: now(),
+            },
+        )
+    }
+
+    /// برداشتن محصول برای بازگردانی؛ اگر مهلتش گذشته باشد None
+    pub fn take(&self, chat_id: i64, product_id: u64) -> TrashResult<Option<DeletedProduct>> {
+        let mut conn = self.conn.lock().map_err(|_| "database lock poisoned")?;
+        let tx = conn.transaction()?;
+
+        let row: Option<(String, i64)> = tx
+            .query_row(
+                "SELECT product, deleted_at FROM trash
+                 WHERE chat_id = ?1 AND product_id = ?2 AND deleted_at > ?3",
+                params![chat_id, product_id as i64, self.expired_before()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let deleted = match row {
+            Some((json, deleted_at)) => {
+                let mut stmt = tx.prepare(
+                    "SELECT filename, bytes, image_alt, is_default FROM trash_images
+                     WHERE chat_id = ?1 AND product_id = ?2 ORDER BY position",
+                )?;
+                let images = stmt
+                    .query_map(params![chat_id, product_id as i64], |row| {
+                        Ok(DeletedImage {
+                            filename: row.get(0)?,
+                            bytes: row.get(1)?,
+                            image_alt: row.get(2)?,
+                            default: row.get(3)?,
+                        })
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                drop(stmt);
+                Some(DeletedProduct {
+                    product: serde_json::from_str(&json)?,
+                    images,
+                    deleted_at,
+                })
+            }
+            None => None,
+        };
+
+        remove(&tx, chat_id, product_id)?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// برگرداندن به سطل (مثلاً وقتی بازگردانی ناموفق بود)؛ مهلت قبلی حفظ می‌شود
+    pub fn put_back(&self, chat_id: i64, deleted: DeletedProduct) -> TrashResult<()> {
+        self.insert(chat_id, &deleted)
+    }
+
+    fn insert(&self, chat_id: i64, deleted: &DeletedProduct) -> TrashResult<()> {
+        let json = serde_json::to_string(&deleted.product)?;
+        let product_id = deleted.product.id;
+
+        let mut conn = self.conn.lock().map_err(|_| "database lock poisoned")?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM trash_images WHERE chat_id = ?1 AND product_id IN
+                 (SELECT product_id FROM trash WHERE chat_id = ?1 AND deleted_at <= ?2)",
+            params![chat_id, self.expired_before()],
+        )?;
+        tx.execute(
+            "DELETE FROM trash WHERE chat_id = ?1 AND deleted_at <= ?2",
+            params![chat_id, self.expired_before()],
+        )?;
+        remove(&tx, chat_id, product_id)?;
+
+        tx.execute(
+            "INSERT INTO trash (chat_id, product_id, product, deleted_at) VALUES (?1, ?2, ?3, ?4)",
+            params![chat_id, product_id as i64, json, deleted.deleted_at],
+        )?;
+        for (position, image) in deleted.images.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO trash_images (chat_id, product_id, position, filename, bytes, image_alt, is_default)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    chat_id,
+                    product_id as i64,
+                    position as i64,
+                    image.filename,
+                    image.bytes,
+                    image.image_alt,
+                    image.default
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// ردیف‌هایی که `deleted_at` آن‌ها تا این لحظه است منقضی شده‌اند
+    fn expired_before(&self) -> i64 {
+        now() - self.ttl.as_secs() as i64
+    }
+}
+
+fn remove(conn: &rusqlite::Connection, chat_id: i64, product_id: u64) -> rusqlite::Result<()> {
+    conn.execute(
+        "DELETE FROM trash_images WHERE chat_id = ?1 AND product_id = ?2",
+        params![chat_id, product_id as i64],
+    )?;
+    conn.execute(
+        "DELETE FROM trash WHERE chat_id = ?1 AND product_id = ?2",
+        params![chat_id, product_id as i64],
+    )?;
+    Ok(())
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+static TRASH: OnceLock<Trash> = OnceLock::new();
+
+/// سطل سراسری روی پایگاه دادهٔ برنامه با مدت نگهداری از تنظیمات
+pub fn trash() -> &'static Trash {
+    TRASH.get_or_init(|| Trash::new(database().clone(), config().trash_ttl()))
+}
+
+/// آخرین محصولی که هر چت ساخته است (برای «برگرداندن آخرین محصول»)، در جدول `last_created`
+pub fn remember_created(chat_id: i64, product_id: u64) {
+    if let Ok(conn) = database().lock()
+        && let Err(e) = conn.execute(
+            "INSERT INTO last_created (chat_id, product_id) VALUES (?1, ?2)
+             ON CONFLICT(chat_id) DO UPDATE SET product_id = excluded.product_id",
+            params![chat_id, product_id as i64],
+        )
+    {
+        eprintln!("could not remember created product of chat {}: {}", chat_id, e);
+    }
+}
+
+pub fn last_created(chat_id: i64) -> Option<u64> {
+    let conn = database().lock().ok()?;
+    conn.query_row(
+        "SELECT product_id FROM last_created WHERE chat_id = ?1",
+        params![chat_id],
+        |row| row.get::<_, i64>(0),
+    )
+    .optional()
+    .ok()?
+    .map(|id| id as u64)
+}
+
+/// فراموش کردن آخرین محصول، اگر همین محصول باشد (بعد از حذف آن)
+pub fn forget_created(chat_id: i64, product_id: u64) {
+    if let Ok(conn) = database().lock()
+        && let Err(e) = conn.execute(
+            "DELETE FROM last_created WHERE chat_id = ?1 AND product_id = ?2",
+            params![chat_id, product_id as i64],
+        )
+    {
+        eprintln!("could not forget created product of chat {}: {}", chat_id, e);
+    }
+}
+
+#[cfg(test)]
+mod test_trash {
+    use super::*;
+    use crate::services::models::product::ProductCreate;
+    use crate::utilities::database::open_in_memory;
+
+    fn product(id: u64) -> Product {
+        Product {
+            id,
+            fields: ProductCreate::new("کفش", 7),
+        }
+    }
+
+    #[test]
+    fn test_take_once_and_expiry() {
+        let conn = open_in_memory().unwrap();
+        let image = DeletedImage {
+            filename: "a.png".into(),
+            bytes: vec![0x89, b'P', b'N', b'G'],
+            image_alt: Some("کفش قرمز".into()),
+            default: true,
+        };
+        Trash::new(conn.clone(), Duration::from_secs(60))
+            .put(1, product(10), vec![image])
+            .unwrap();
+
+        // نمونهٔ جدید روی همان پایگاه داده (مثل بعد از ری‌استارت)
+        let trash = Trash::new(conn.clone(), Duration::from_secs(60));
+        assert!(trash.take(2, 10).unwrap().is_none());
+        let taken = trash.take(1, 10).unwrap().unwrap();
+        assert_eq!(taken.product.fields.name, "کفش");
+        let create = taken.images[0].to_create(55);
+        assert_eq!(create.pk, 55);
+        assert_eq!(create.image_alt.as_deref(), Some("کفش قرمز"));
+        assert_eq!(create.default, Some(true));
+        assert_eq!(create.image.unwrap().bytes, vec![0x89, b'P', b'N', b'G']);
+        assert!(trash.take(1, 10).unwrap().is_none());
+
+        let expired = Trash::new(conn, Duration::ZERO);
+        expired.put(1, product(11), Vec::new()).unwrap();
+        assert!(expired.take(1, 11).unwrap().is_none());
+    }
+}
@@ -0,0 +1,421 @@
+use crate::services::category_cache::{cached_categories, category_cache};
+use crate::services::models::category::Category;
+use crate::services::models::product::ProductField;
+use crate::services::torob_client::TorobClient;
+use crate::telegram_infrastructure::category_picker::{
+    CategoryTree, MANAGE_TREE, PARENT_TREE, PickerAction, parse_action, tree_view,
+};
+use crate::telegram_infrastructure::endpoints::{ensure_idle, HandlerResult, MyDialogue};
+use crate::telegram_infrastructure::models::category_draft::CategoryDraft;
+use crate::telegram_infrastructure::models::state::State;
+use crate::telegram_infrastructure::product_wizard::product_wizard;
+use crate::telegram_infrastructure::wizard::{Step, Wizard};
+use crate::utilities::text::parse_number;
+use serde_json::json;
+use std::sync::{Arc, LazyLock};
+use teloxide::Bot;
+use teloxide::payloads::{EditMessageTextSetters, SendMessageSetters};
+use teloxide::prelude::{CallbackQuery, ChatId, Message};
+use teloxide::requests::Requester;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+/// جریان ساخت دسته: نام ← والد ← فعال بودن
+static CATEGORY_WIZARD: LazyLock<Wizard<CategoryDraft>> = LazyLock::new(build);
+
+pub fn category_wizard() -> &'static Wizard<CategoryDraft> {
+    &CATEGORY_WIZARD
+}
+
+fn build() -> Wizard<CategoryDraft> {
+    Wizard::new(
+        |step, draft| State::CreateCategory { step, draft },
+        |bot, dialogue, draft| Box::pin(create_category(bot, dialogue, draft)),
+    )
+    .on_cancel(|bot, dialogue, draft| {
+        Box::pin(async move {
+            bot.send_message(dialogue.chat_id(), "ساخت دسته‌بندی لغو شد.")
+                .await?;
+            back_to_origin(&bot, dialogue, draft).await
+        })
+    })
+    .step(
+        Step::new(
+            "name",
+            |_| "نام دسته‌بندی جدید را وارد کنید.".into(),
+            |d: &mut CategoryDraft, text| {
+                let name = text.trim();
+                if name.is_empty() {
+                    return Err("نام خالی است؛ دوباره وارد کنید.".into());
+                }
+                d.category.name = name.to_string();
+                Ok(())
+            },
+        )
+        .current(|d: &CategoryDraft| Some(d.category.name.clone()).filter(|n| !n.is_empty())),
+    )
+    .step(
+        Step::new(
+            "parent",
+            |_| "دستهٔ والد را از درخت بالا انتخاب کنید، یا «بدون والد» را بزنید (یا شناسهٔ والد را بفرستید).".into(),
+            |d: &mut CategoryDraft, text| {
+                d.category.parent = match text.trim() {
+                    "root" => None,
+                    t => Some(parse_number::<u64>(t).ok_or("شناسه نامعتبر است؛ فقط عدد بفرستید.")?),
+                };
+                Ok(())
+            },
+        )
+        .choices(|_| vec![("🌳 بدون والد (ریشه)".into(), "root".into())])
+        .current(|d: &CategoryDraft| d.category.parent.map(|p| format!("دستهٔ {}", p)))
+        .load(|d, chat_id| {
+            Box::pin(async move {
+                let cats = load_categories(chat_id).await?;
+                let (text, markup) = tree_view(&CategoryTree::new(&cats), d.category.parent, 0, &PARENT_TREE);
+                Ok(vec![(text, Some(markup))])
+            })
+        })
+        .validate(|d, chat_id| {
+            Box::pin(async move {
+                if let Some(parent) = d.category.parent {
+                    let cats = load_categories(chat_id).await?;
+                    if !cats.iter().any(|c| c.id == parent) {
+                        return Err("چنین دسته‌ای وجود ندارد؛ دوباره انتخاب کنید.".into());
+                    }
+                }
+                Ok(d)
+            })
+        }),
+    )
+    .step(
+        Step::new(
+            "available",
+            |_| "این دسته‌بندی فعال باشد؟".into(),
+            |d: &mut CategoryDraft, text| {
+                d.category.available = match text.trim() {
+                    "yes" | "بله" => true,
+                    "no" | "خیر" => false,
+                    _ => return Err("«بله» یا «خیر» را انتخاب کنید.".into()),
+                };
+                Ok(())
+            },
+        )
+        .choices(|_| {
+            vec![
+                ("✅ بله".into(), "yes".into()),
+                ("⛔️ خیر".into(), "no".into()),
+            ]
+        }),
+    )
+}
+
+async fn load_categories(chat_id: ChatId) -> Result<Arc<Vec<Category>>, String> {
+    cached_categories(&chat_id.0.to_string()).await.map_err(|e| {
+        eprintln!("error in fetching categories: {}", e);
+        format!("❌ خطا در دریافت دسته‌بندی‌ها:\n{}", e.user_message())
+    })
+}
+
+/// ساخت دسته در پنل و برگشت به جریانی که از آن شروع شده بود
+async fn create_category(bot: Bot, dialogue: MyDialogue, draft: CategoryDraft) -> HandlerResult {
+    let chat_id = dialogue.chat_id();
+    let created = match TorobClient::for_chat(&chat_id.0.to_string()) {
+        Ok(client) => client.create_category(&draft.category).await,
+        Err(e) => Err(e),
+    };
+
+    let category = match created {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error in creating category: {}", e);
+            bot.send_message(chat_id, format!("❌ خطا در ساخت دسته‌بندی:\n{}", e.user_message()))
+                .await?;
+            return back_to_origin(&bot, dialogue, draft).await;
+        }
+    };
+
+    category_cache().upsert(&chat_id.0.to_string(), category.clone());
+    bot.send_message(
+        chat_id,
+        format!("✅ دستهٔ «{}» ساخته شد (id: {}).", category.name, category.id),
+    )
+    .await?;
+
+    match draft.resume {
+        // دستهٔ فعال مستقیم برای محصول انتخاب می‌شود
+        Some((step, product)) if category.available => {
+            dialogue
+                .update(State::CreateProduct { step: step.clone(), draft: product.clone() })
+                .await?;
+            product_wizard()
+                .submit(&bot, dialogue, &step, product, &category.id.to_string())
+                .await
+        }
+        _ => back_to_origin(&bot, dialogue, draft).await,
+    }
+}
+
+/// برگشت به مرحلهٔ دسته‌بندی محصول (اگر از آنجا آمده) یا به شروع
+async fn back_to_origin(bot: &Bot, dialogue: MyDialogue, draft: CategoryDraft) -> HandlerResult {
+    match draft.resume {
+        Some((_, product)) => {
+            product_wizard()
+                .ask_step(bot, dialogue, ProductField::MainCategory.key(), product)
+                .await
+        }
+        None => {
+            dialogue.update(State::Start).await?;
+            Ok(())
+        }
+    }
+}
+
+/// پاسخ متنی به مرحلهٔ فعلی ساخت دسته
+pub async fn receive_step(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    step_draft: (String, CategoryDraft),
+) -> HandlerResult {
+    let (step, draft) = step_draft;
+    category_wizard()
+        .handle_message(&bot, dialogue, &msg, &step, draft)
+        .await
+}
+
+/// دکمه‌های ساخت دسته؛ `cp:*` درخت انتخاب والد است
+pub async fn step_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    step_draft: (String, CategoryDraft),
+) -> HandlerResult {
+    let (step, draft) = step_draft;
+    let Some(data) = q.data.as_deref().filter(|d| d.starts_with("cp:")) else {
+        return category_wizard()
+            .handle_callback(&bot, dialogue, &q, &step, draft)
+            .await;
+    };
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    let chat_id = dialogue.chat_id();
+    let at_parent = step == "parent";
+    let (Some(action), true, Some(m)) = (parse_action(data), at_parent, q.message.as_ref()) else {
+        return Ok(());
+    };
+    let cats = match load_categories(chat_id).await {
+        Ok(cats) => cats,
+        Err(why) => {
+            bot.send_message(chat_id, why).await?;
+            return Ok(());
+        }
+    };
+    let tree = CategoryTree::new(&cats);
+
+    match action {
+        PickerAction::Open { node, page } => {
+            let (text, markup) = tree_view(&tree, node, page, &PARENT_TREE);
+            bot.edit_message_text(chat_id, m.id, text)
+                .reply_markup(markup)
+                .await?;
+            Ok(())
+        }
+        PickerAction::Select(id) => {
+            bot.edit_message_text(chat_id, m.id, format!("📂 والد: {}", tree.path_text(id)))
+                .await?;
+            category_wizard()
+                .submit(&bot, dialogue, &step, draft, &id.to_string())
+                .await
+        }
+        PickerAction::New { .. } | PickerAction::Disabled => Ok(()),
+    }
+}
+
+/// ====== مدیریت دسته‌ها (/categories) ======
+///
+/// - `cm:o|s|new|n:*` درخت (مثل `category_picker`)
+/// - `cm:r:{id}` تغییر نام
+/// - `cm:a:{id}` فعال/غیرفعال کردن
+pub fn is_manage_callback(q: CallbackQuery) -> bool {
+    q.data.as_deref().is_some_and(|d| d.starts_with("cm:"))
+}
+
+pub async fn start_manage(bot: Bot, msg: Message) -> HandlerResult {
+    let cats = match load_categories(msg.chat.id).await {
+        Ok(cats) => cats,
+        Err(why) => {
+            bot.send_message(msg.chat.id, why).await?;
+            return Ok(());
+        }
+    };
+    let (text, markup) = tree_view(&CategoryTree::new(&cats), None, 0, &MANAGE_TREE);
+    bot.send_message(msg.chat.id, text).reply_markup(markup).await?;
+    Ok(())
+}
+
+/// شروع ساخت دسته با دستور /newcategory
+pub async fn start_new_category(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+    if let Err(e) = TorobClient::for_chat(&msg.chat.id.0.to_string()) {
+        bot.send_message(msg.chat.id, e.user_message()).await?;
+        return Ok(());
+    }
+    category_wizard()
+        .start(&bot, dialogue, CategoryDraft::new(None, None))
+        .await
+}
+
+pub async fn manage_callback(bot: Bot, dialogue: MyDialogue, q: CallbackQuery) -> HandlerResult {
+    let parts: Vec<&str> = q.data.as_deref().unwrap_or_default().split(':').collect();
+    // تغییر نام و ساخت زیردسته گفتگوی تازه‌ای شروع می‌کنند
+    if matches!(parts.get(1).copied(), Some("r" | "new")) && !ensure_idle(&bot, &dialogue, &q).await? {
+        return Ok(());
+    }
+    bot.answer_callback_query(q.id.clone()).await?;
+    let (Some(data), Some(m)) = (q.data.as_deref(), q.message.as_ref()) else {
+        return Ok(());
+    };
+    let chat_id = m.chat.id;
+    let id = parts.get(2).and_then(|x| x.parse::<u64>().ok());
+
+    match (parts.get(1).copied(), id) {
+        (Some("r"), Some(id)) => {
+            bot.edit_message_reply_markup(chat_id, m.id).await?;
+            bot.send_message(chat_id, "نام جدید دسته را بفرستید (یا /cancel).")
+                .await?;
+            dialogue.update(State::RenameCategory { category_id: id }).await?;
+            return Ok(());
+        }
+        (Some("a"), Some(id)) => {
+            let cats = match load_categories(chat_id).await {
+                Ok(cats) => cats,
+                Err(why) => {
+                    bot.send_message(chat_id, why).await?;
+                    return Ok(());
+                }
+            };
+            let Some(current) = cats.iter().find(|c| c.id == id) else {
+                bot.send_message(chat_id, "این دسته دیگر وجود ندارد.").await?;
+                return Ok(());
+            };
+            let patch = json!({ "available": !current.available });
+            match update_category(chat_id, id, &patch).await {
+                Ok(tree) => {
+                    let (text, markup) = category_card(&tree, id);
+                    bot.edit_message_text(chat_id, m.id, text)
+                        .reply_markup(markup)
+                        .await?;
+                }
+                Err(why) => {
+                    bot.send_message(chat_id, why).await?;
+                }
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let Some(action) = parse_action(data) else {
+        return Ok(());
+    };
+    if let PickerAction::New { parent } = action {
+        bot.edit_message_reply_markup(chat_id, m.id).await?;
+        return category_wizard()
+            .start(&bot, dialogue, CategoryDraft::new(parent, None))
+            .await;
+    }
+
+    let cats = match load_categories(chat_id).await {
+        Ok(cats) => cats,
+        Err(why) => {
+            bot.send_message(chat_id, why).await?;
+            return Ok(());
+        }
+    };
+    let tree = CategoryTree::new(&cats);
+    let (text, markup) = match action {
+        PickerAction::Open { node, page } => tree_view(&tree, node, page, &MANAGE_TREE),
+        PickerAction::Select(id) if tree.get(id).is_some() => category_card(&tree, id),
+        _ => return Ok(()),
+    };
+    bot.edit_message_text(chat_id, m.id, text)
+        .reply_markup(markup)
+        .await?;
+    Ok(())
+}
+
+/// دریافت نام جدید دسته
+pub async fn receive_new_name(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    category_id: u64,
+) -> HandlerResult {
+    let Some(name) = msg.text().map(str::trim).filter(|t| !t.is_empty()) else {
+        bot.send_message(msg.chat.id, "لطفاً نام جدید را به صورت متن بفرستید.")
+            .await?;
+        return Ok(());
+    };
+    if name == "/cancel" {
+        bot.send_message(msg.chat.id, "تغییر نام لغو شد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+
+    match update_category(msg.chat.id, category_id, &json!({ "name": name })).await {
+        Ok(tree) => {
+            let (text, markup) = category_card(&tree, category_id);
+            bot.send_message(msg.chat.id, format!("✅ نام دسته تغییر کرد.\n\n{}", text))
+                .reply_markup(markup)
+                .await?;
+            dialogue.update(State::Start).await?;
+        }
+        Err(why) => {
+            bot.send_message(msg.chat.id, why).await?;
+        }
+    }
+    Ok(())
+}
+
+/// PATCH دسته و به‌روز کردن کش؛ درخت تازه برای نمایش کارت برگردانده می‌شود
+async fn update_category(
+    chat_id: ChatId,
+    id: u64,
+    patch: &serde_json::Value,
+) -> Result<CategoryTree, String> {
+    let chat = chat_id.0.to_string();
+    let updated = match TorobClient::for_chat(&chat) {
+        Ok(client) => client.update_category(id, patch).await,
+        Err(e) => Err(e),
+    };
+    let category = updated.map_err(|e| {
+        eprintln!("error in updating category {}: {}", id, e);
+        format!("❌ خطا در ویرایش دسته‌بندی:\n{}", e.user_message())
+    })?;
+    category_cache().upsert(&chat, category);
+    let cats = load_categories(chat_id).await?;
+    Ok(CategoryTree::new(&cats))
+}
+
+/// کارت یک دسته با دکمه‌های مدیریت
+fn category_card(tree: &CategoryTree, id: u64) -> (String, InlineKeyboardMarkup) {
+    let Some(c) = tree.get(id) else {
+        return ("این دسته دیگر وجود ندارد.".into(), InlineKeyboardMarkup::default());
+    };
+    let status = if c.available { "فعال ✅" } else { "غیرفعال ⛔️" };
+    let text = format!("🗂 {}\n🆔 {}\nوضعیت: {}", tree.path_text(id), id, status);
+
+    let toggle = if c.available { "⛔️ غیرفعال کردن" } else { "✅ فعال کردن" };
+    let parent = c
+        .parent
+        .filter(|p| tree.get(*p).is_some())
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| "root".into());
+    let markup = InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("✏️ تغییر نام", format!("cm:r:{}", id)),
+            InlineKeyboardButton::callback(toggle, format!("cm:a:{}", id)),
+        ],
+        vec![InlineKeyboardButton::callback("➕ زیردستهٔ جدید", format!("cm:new:{}", id))],
+        vec![InlineKeyboardButton::callback("⬆️ بازگشت", format!("cm:o:{}:0", parent))],
+    ]);
+    (text, markup)
+}
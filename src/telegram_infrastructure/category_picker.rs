@@ -0,0 +1,534 @@
+use crate::services::models::category::Category;
+use crate::utilities::text::normalize_search;
+use std::collections::{HashMap, HashSet};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+/// تعداد دسته‌بندی در هر صفحه از یک سطح
+const PAGE_SIZE: usize = 8;
+/// حداکثر نتایج جستجوی متنی
+const SEARCH_LIMIT: usize = 8;
+
+/// ====== درخت دسته‌بندی‌ها ======
+/// ساخته‌شده از `Category.parent`؛ دسته‌ای که والدش در لیست نیست ریشه حساب می‌شود.
+pub struct CategoryTree {
+    by_id: HashMap<u64, Category>,
+    children: HashMap<Option<u64>, Vec<u64>>,
+}
+
+impl CategoryTree {
+    pub fn new(cats: &[Category]) -> Self {
+        let by_id: HashMap<u64, Category> = cats.iter().map(|c| (c.id, c.clone())).collect();
+        let mut children: HashMap<Option<u64>, Vec<u64>> = HashMap::new();
+        for c in cats {
+            let parent = c
+                .parent
+                .filter(|p| *p != c.id && by_id.contains_key(p));
+            children.entry(parent).or_default().push(c.id);
+        }
+        for ids in children.values_mut() {
+            ids.sort_by(|a, b| by_id[a].name.cmp(&by_id[b].name).then(a.cmp(b)));
+        }
+        Self { by_id, children }
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Category> {
+        self.by_id.get(&id)
+    }
+
+    /// زیرشاخه‌های یک گره (`None` یعنی ریشه)
+    pub fn children(&self, node: Option<u64>) -> Vec<&Category> {
+        self.children
+            .get(&node)
+            .map(|ids| ids.iter().map(|id| &self.by_id[id]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn has_children(&self, id: u64) -> bool {
+        self.children.get(&Some(id)).is_some_and(|c| !c.is_empty())
+    }
+
+    /// مسیر از ریشه تا این دسته (در برابر والد حلقه‌ای مقاوم است)
+    pub fn breadcrumb(&self, id: u64) -> Vec<&Category> {
+        let mut path = Vec::new();
+        let mut seen = HashSet::new();
+        let mut current = self.get(id);
+        while let Some(c) = current {
+            if !seen.insert(c.id) {
+                break;
+            }
+            path.push(c);
+            current = c.parent.filter(|p| *p != c.id).and_then(|p| self.get(p));
+        }
+        path.reverse();
+        path
+    }
+
+    /// مسیر به صورت متن: «پوشاک › مردانه › کفش»
+    pub fn path_text(&self, id: u64) -> String {
+        self.breadcrumb(id)
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" › ")
+    }
+
+    /// جستجوی تقریبی در نام و مسیر دسته‌ها؛ بهترین نتایج اول
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&Category> {
+        let query = normalize_search(query);
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(u32, &Category)> = self
+            .by_id
+            .values()
+            .filter_map(|c| {
+                let score = match_score(&query, &normalize_search(&c.name), &normalize_search(&self.path_text(c.id)));
+                (score > 0).then_some((score, c))
+            })
+            .collect();
+        scored.sort_by(|(sa, a), (sb, b)| {
+            sb.cmp(sa)
+                .then(b.available.cmp(&a.available))
+                .then(a.name.len().cmp(&b.name.len()))
+                .then(a.id.cmp(&b.id))
+        });
+        scored.into_iter().take(limit).map(|(_, c)| c).collect()
+    }
+}
+
+/// امتیاز تطابق عبارت با نام/مسیر نرمال‌شده (۰ یعنی بی‌ربط)
+fn match_score(query: &str, name: &str, path: &str) -> u32 {
+    if name == query {
+        return 100;
+    }
+    if name.starts_with(query) {
+        return 80;
+    }
+    if name.contains(query) {
+        return 60;
+    }
+    let words: Vec<&str> = path.split(' ').collect();
+    let tokens: Vec<&str> = query.split(' ').collect();
+    if tokens.iter().all(|t| path.contains(t)) {
+        return 40;
+    }
+    // هر کلمه با حداکثر یک غلط تایپی (برای کلمات بلندتر از ۳ حرف)
+    let fuzzy = tokens.iter().all(|t| {
+        let len = t.chars().count();
+        words
+            .iter()
+            .any(|w| w.starts_with(t) || (len > 3 && edit_distance(t, w) <= 1))
+    });
+    if fuzzy { 20 } else { 0 }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// ====== دکمه‌های درخت دسته‌بندی ======
+///
+/// پیشوند هر کاربرد جداست (`TreeMode.prefix`)؛ مثلاً برای انتخاب دستهٔ محصول `cat`:
+/// - `cat:o:{id|root}:{page}` باز کردن یک سطح
+/// - `cat:s:{id}` انتخاب
+/// - `cat:new:{id|root}` ساخت دستهٔ جدید زیر این سطح
+/// - `cat:n` دکمهٔ دستهٔ غیرفعال
+/// - `cat:i` شمارهٔ صفحه (بی‌اثر)
+#[derive(Debug, PartialEq)]
+pub enum PickerAction {
+    Open { node: Option<u64>, page: usize },
+    Select(u64),
+    New { parent: Option<u64> },
+    Disabled,
+}
+
+/// رفتار درخت در هر کاربرد
+pub struct TreeMode {
+    pub prefix: &'static str,
+    pub title: &'static str,
+    /// دسته‌های غیرفعال هم قابل انتخاب‌اند (مثلاً برای مدیریت یا انتخاب والد)
+    pub select_unavailable: bool,
+    /// دکمهٔ «دستهٔ جدید» در هر سطح
+    pub allow_new: bool,
+}
+
+/// انتخاب دستهٔ اصلی محصول
+pub const PRODUCT_TREE: TreeMode = TreeMode {
+    prefix: "cat",
+    title: "دسته‌بندی محصول را انتخاب کنید",
+    select_unavailable: false,
+    allow_new: true,
+};
+
+/// انتخاب والد دستهٔ جدید
+pub const PARENT_TREE: TreeMode = TreeMode {
+    prefix: "cp",
+    title: "دستهٔ والد را انتخاب کنید",
+    select_unavailable: true,
+    allow_new: false,
+};
+
+/// مدیریت دسته‌ها (/categories)
+pub const MANAGE_TREE: TreeMode = TreeMode {
+    prefix: "cm",
+    title: "دسته‌ای را برای مدیریت انتخاب کنید",
+    select_unavailable: true,
+    allow_new: true,
+};
+
+pub fn is_category_callback(data: &str) -> bool {
+    data.starts_with("cat:")
+}
+
+/// خواندن دکمهٔ درخت (پیشوند را خود handler بررسی کرده است)
+pub fn parse_action(data: &str) -> Option<PickerAction> {
+    let (_, rest) = data.split_once(':')?;
+    let mut parts = rest.split(':');
+    let node = |part: Option<&str>| -> Option<Option<u64>> {
+        match part? {
+            "root" => Some(None),
+            id => Some(Some(id.parse().ok()?)),
+        }
+    };
+    match parts.next()? {
+        "o" => {
+            let node = node(parts.next())?;
+            let page = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+            Some(PickerAction::Open { node, page })
+        }
+        "s" => Some(PickerAction::Select(parts.next()?.parse().ok()?)),
+        "new" => Some(PickerAction::New {
+            parent: node(parts.next())?,
+        }),
+        "n" => Some(PickerAction::Disabled),
+        _ => None,
+    }
+}
+
+fn node_key(node: Option<u64>) -> String {
+    node.map(|id| id.to_string()).unwrap_or_else(|| "root".into())
+}
+
+fn open_data(prefix: &str, node: Option<u64>, page: usize) -> String {
+    format!("{}:o:{}:{}", prefix, node_key(node), page)
+}
+
+/// متن (breadcrumb) و دکمه‌های یک سطح از درخت برای انتخاب دستهٔ محصول
+pub fn picker_view(tree: &CategoryTree, node: Option<u64>, page: usize) -> (String, InlineKeyboardMarkup) {
+    tree_view(tree, node, page, &PRODUCT_TREE)
+}
+
+pub fn tree_view(
+    tree: &CategoryTree,
+    node: Option<u64>,
+    page: usize,
+    mode: &TreeMode,
+) -> (String, InlineKeyboardMarkup) {
+    let prefix = mode.prefix;
+    let node = node.filter(|id| tree.get(*id).is_some());
+    let children = tree.children(node);
+    let pages = children.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let mut text = match node {
+        Some(id) => format!("📂 ریشه › {}", tree.path_text(id)),
+        None => "📂 ریشه".to_string(),
+    };
+    text.push_str(&format!("\n\n{} (📁 یعنی زیرشاخه دارد، ⛔️ غیرفعال است).", mode.title));
+
+    let select = |c: &Category, label: String| {
+        if c.available {
+            InlineKeyboardButton::callback(label, format!("{}:s:{}", prefix, c.id))
+        } else if mode.select_unavailable {
+            InlineKeyboardButton::callback(format!("⛔️ {}", label), format!("{}:s:{}", prefix, c.id))
+        } else {
+            InlineKeyboardButton::callback(format!("⛔️ {}", c.name), format!("{}:n", prefix))
+        }
+    };
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+
+    // انتخاب خود دسته‌ای که زیرشاخه‌هایش باز شده
+    if let Some(c) = node.and_then(|id| tree.get(id)) {
+        rows.push(vec![select(c, format!("✅ همین: {}", c.name))]);
+    }
+
+    for c in children.iter().skip(page * PAGE_SIZE).take(PAGE_SIZE) {
+        let button = if tree.has_children(c.id) {
+            InlineKeyboardButton::callback(format!("📁 {}", c.name), open_data(prefix, Some(c.id), 0))
+        } else {
+            select(c, c.name.clone())
+        };
+        rows.push(vec![button]);
+    }
+
+    if pages > 1 {
+        let mut nav = Vec::new();
+        if page > 0 {
+            nav.push(InlineKeyboardButton::callback("◀️ قبلی", open_data(prefix, node, page - 1)));
+        }
+        nav.push(InlineKeyboardButton::callback(
+            format!("{}/{}", page + 1, pages),
+            format!("{}:i", prefix),
+        ));
+        if page + 1 < pages {
+            nav.push(InlineKeyboardButton::callback("بعدی ▶️", open_data(prefix, node, page + 1)));
+        }
+        rows.push(nav);
+    }
+
+    let mut bottom = Vec::new();
+    if let Some(c) = node.and_then(|id| tree.get(id)) {
+        let parent = c.parent.filter(|p| tree.get(*p).is_some());
+        bottom.push(InlineKeyboardButton::callback("⬆️ سطح بالاتر", open_data(prefix, parent, 0)));
+    }
+    if mode.allow_new {
+        bottom.push(InlineKeyboardButton::callback(
+            "➕ دستهٔ جدید",
+            format!("{}:new:{}", prefix, node_key(node)),
+        ));
+    }
+    if !bottom.is_empty() {
+        rows.push(bottom);
+    }
+
+    (text, InlineKeyboardMarkup::new(rows))
+}
+
+/// دسته‌های پیشنهادی (از روی نام محصول) و دکمهٔ مرور همهٔ دسته‌ها
+pub fn suggestions_view(tree: &CategoryTree, ids: &[u64]) -> (String, InlineKeyboardMarkup) {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = ids
+        .iter()
+        .filter(|id| tree.get(**id).is_some())
+        .map(|id| vec![InlineKeyboardButton::callback(format!("⭐️ {}", tree.path_text(*id)), format!("cat:s:{}", id))])
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback("📂 مرور همهٔ دسته‌بندی‌ها", open_data("cat", None, 0))]);
+    (
+        "دسته‌بندی‌های پیشنهادی برای این محصول:".to_string(),
+        InlineKeyboardMarkup::new(rows),
+    )
+}
+
+/// نتایج جستجوی متنی با مسیر کامل هر دسته
+pub fn search_view(tree: &CategoryTree, query: &str) -> (String, InlineKeyboardMarkup) {
+    let found = tree.search(query, SEARCH_LIMIT);
+    let text = if found.is_empty() {
+        format!("دسته‌ای شبیه «{}» پیدا نشد؛ عبارت دیگری بفرستید یا درخت را مرور کنید.", query.trim())
+    } else {
+        format!("نتایج جستجو برای «{}»:", query.trim())
+    };
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = found
+        .iter()
+        .map(|c| {
+            let path = tree.path_text(c.id);
+            vec![if c.available {
+                InlineKeyboardButton::callback(path, format!("cat:s:{}", c.id))
+            } else {
+                InlineKeyboardButton::callback(format!("⛔️ {}", path), "cat:n")
+            }]
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback("📂 مرور درخت", open_data("cat", None, 0))]);
+    (text, InlineKeyboardMarkup::new(rows))
+}
+
+/// ====== چک‌لیست دسته‌بندی‌های دیگر (`other_categories`) ======
+///
+/// - `oc:t:{id}:{page}` تیک زدن/برداشتن
+/// - `oc:p:{page}` رفتن به صفحه
+/// - `oc:d` پایان انتخاب
+/// - `oc:n` شمارهٔ صفحه (بی‌اثر)
+#[derive(Debug, PartialEq)]
+pub enum ChecklistAction {
+    Toggle { id: u64, page: usize },
+    Page(usize),
+    Done,
+}
+
+pub fn is_checklist_callback(data: &str) -> bool {
+    data.starts_with("oc:")
+}
+
+pub fn parse_checklist_action(data: &str) -> Option<ChecklistAction> {
+    let mut parts = data.strip_prefix("oc:")?.split(':');
+    match parts.next()? {
+        "t" => {
+            let id = parts.next()?.parse().ok()?;
+            let page = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+            Some(ChecklistAction::Toggle { id, page })
+        }
+        "p" => Some(ChecklistAction::Page(parts.next()?.parse().ok()?)),
+        "d" => Some(ChecklistAction::Done),
+        _ => None,
+    }
+}
+
+/// دسته‌های قابل انتخاب: هم‌سطح‌های دستهٔ اصلی اول، بعد بقیه به ترتیب مسیر؛
+/// موارد تیک‌خورده حتی اگر غیرفعال شده باشند می‌مانند تا بشود برشان داشت.
+fn checklist_candidates(tree: &CategoryTree, main: u64, selected: &[u64]) -> Vec<(u64, String)> {
+    let main_parent = tree.get(main).and_then(|c| c.parent);
+    let mut items: Vec<(bool, String, u64)> = tree
+        .by_id
+        .values()
+        .filter(|c| c.id != main && (c.available || selected.contains(&c.id)))
+        .map(|c| (c.parent != main_parent, tree.path_text(c.id), c.id))
+        .collect();
+    items.sort();
+    items.into_iter().map(|(_, path, id)| (id, path)).collect()
+}
+
+pub fn checklist_view(
+    tree: &CategoryTree,
+    main: u64,
+    selected: &[u64],
+    page: usize,
+) -> (String, InlineKeyboardMarkup) {
+    let items = checklist_candidates(tree, main, selected);
+    let pages = items.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let mut text = format!("دسته‌بندی‌های دیگر این محصول (انتخاب‌شده: {})", selected.len());
+    for id in selected {
+        text.push_str(&format!("\n☑️ {}", tree.path_text(*id)));
+    }
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = items
+        .iter()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .map(|(id, path)| {
+            let mark = if selected.contains(id) { "☑️" } else { "⬜️" };
+            vec![InlineKeyboardButton::callback(
+                format!("{} {}", mark, path),
+                format!("oc:t:{}:{}", id, page),
+            )]
+        })
+        .collect();
+
+    if pages > 1 {
+        let mut nav = Vec::new();
+        if page > 0 {
+            nav.push(InlineKeyboardButton::callback("◀️ قبلی", format!("oc:p:{}", page - 1)));
+        }
+        nav.push(InlineKeyboardButton::callback(format!("{}/{}", page + 1, pages), "oc:n"));
+        if page + 1 < pages {
+            nav.push(InlineKeyboardButton::callback("بعدی ▶️", format!("oc:p:{}", page + 1)));
+        }
+        rows.push(nav);
+    }
+    rows.push(vec![InlineKeyboardButton::callback("✅ پایان انتخاب", "oc:d")]);
+    (text, InlineKeyboardMarkup::new(rows))
+}
+
+#[cfg(test)]
+mod test_category_picker {
+    use super::*;
+
+    #[test]
+    fn test_tree_breadcrumb_and_paging() {
+        let mut cats = vec![
+            Category::for_test(1, "پوشاک", None, true),
+            Category::for_test(2, "کفش", Some(1), true),
+            Category::for_test(3, "قدیمی", Some(1), false),
+            // والد ناموجود و والد حلقه‌ای
+            Category::for_test(4, "یتیم", Some(999), true),
+            Category::for_test(5, "حلقه", Some(5), true),
+        ];
+        for i in 0..10 {
+            cats.push(Category::for_test(100 + i, &format!("زیر {}", i), Some(2), true));
+        }
+        let tree = CategoryTree::new(&cats);
+
+        assert_eq!(tree.children(None).len(), 3);
+        assert_eq!(tree.path_text(105), "پوشاک › کفش › زیر 5");
+
+        let (text, kb) = picker_view(&tree, Some(1), 0);
+        assert!(text.contains("ریشه › پوشاک"));
+        // «همین»، قدیمی (غیرفعال)، کفش (📁)، بالا/دستهٔ جدید
+        assert_eq!(kb.inline_keyboard.len(), 4);
+        assert_eq!(kb.inline_keyboard[1][0].text, "⛔️ قدیمی");
+
+        let (_, kb) = picker_view(&tree, Some(2), 5);
+        // صفحهٔ آخر: «همین» + ۲ مورد + ناوبری + بالا/دستهٔ جدید
+        assert_eq!(kb.inline_keyboard.len(), 5);
+
+        // در مدیریت، دستهٔ غیرفعال قابل انتخاب است و دکمه‌ها پیشوند خودشان را دارند
+        let (_, kb) = tree_view(&tree, Some(1), 0, &MANAGE_TREE);
+        assert_eq!(
+            kb.inline_keyboard[1][0].kind,
+            teloxide::types::InlineKeyboardButtonKind::CallbackData("cm:s:3".into())
+        );
+        assert_eq!(
+            parse_action("cm:new:root"),
+            Some(PickerAction::New { parent: None })
+        );
+
+        assert_eq!(
+            parse_action("cat:o:root:2"),
+            Some(PickerAction::Open { node: None, page: 2 })
+        );
+        assert_eq!(parse_action("cat:s:7"), Some(PickerAction::Select(7)));
+        assert_eq!(parse_action("cat:x"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let cats = vec![
+            Category::for_test(1, "پوشاک مردانه", None, true),
+            Category::for_test(2, "کفش", Some(1), true),
+            Category::for_test(3, "پوشاک زنانه", None, true),
+            Category::for_test(4, "کفش", Some(3), false),
+            Category::for_test(5, "Mobile Phones", None, true),
+        ];
+        let tree = CategoryTree::new(&cats);
+
+        // ک عربی و نتایج هم‌نام با مسیر متفاوت؛ فعال‌ها اول
+        let found = tree.search("كفش", 8);
+        assert_eq!(found.iter().map(|c| c.id).collect::<Vec<_>>(), vec![2, 4]);
+        assert_eq!(tree.search("mobile", 8)[0].id, 5);
+        // غلط تایپی
+        assert_eq!(tree.search("mobil phones", 8)[0].id, 5);
+        assert_eq!(tree.search("زنانه کفش", 8)[0].id, 4);
+        assert!(tree.search("لپتاپ", 8).is_empty());
+
+        let (_, kb) = search_view(&tree, "کفش");
+        assert_eq!(kb.inline_keyboard[0][0].text, "پوشاک مردانه › کفش");
+    }
+
+    #[test]
+    fn test_checklist_order_and_marks() {
+        let cats = vec![
+            Category::for_test(1, "پوشاک", None, true),
+            Category::for_test(2, "کفش", Some(1), true),
+            Category::for_test(3, "کیف", Some(1), true),
+            Category::for_test(4, "اکسسوری", None, true),
+            Category::for_test(5, "قدیمی", Some(1), false),
+        ];
+        let tree = CategoryTree::new(&cats);
+
+        // دستهٔ اصلی ۲؛ هم‌سطح (۳ و ۵ی تیک‌خورده) اول، غیرفعالِ تیک‌نخورده نمی‌آید
+        let ids: Vec<u64> = checklist_candidates(&tree, 2, &[5]).iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![5, 3, 4, 1]);
+        let ids: Vec<u64> = checklist_candidates(&tree, 2, &[]).iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![3, 4, 1]);
+
+        let (_, kb) = checklist_view(&tree, 2, &[3], 0);
+        assert!(kb.inline_keyboard[0][0].text.starts_with("☑️"));
+        assert_eq!(
+            parse_checklist_action("oc:t:3:0"),
+            Some(ChecklistAction::Toggle { id: 3, page: 0 })
+        );
+    }
+}
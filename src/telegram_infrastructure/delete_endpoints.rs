@@ -0,0 +1,421 @@
+use crate::services::models::product::Product;
+use crate::services::torob_client::TorobClient;
+use crate::services::torob_error::TorobError;
+use crate::services::trash::{forget_created, last_created, trash, DeletedImage};
+use crate::telegram_infrastructure::endpoints::{client, report, HandlerResult, MyDialogue};
+use crate::telegram_infrastructure::models::state::State;
+use crate::telegram_infrastructure::product_picker::pick_product;
+use teloxide::Bot;
+use teloxide::payloads::{AnswerCallbackQuerySetters, EditMessageTextSetters, SendMessageSetters};
+use teloxide::prelude::{CallbackQuery, ChatId, Message};
+use teloxide::requests::Requester;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId};
+
+/// آیا این callback مربوط به حذف/بازگردانی است (مستقل از وضعیت مکالمه)
+pub fn is_delete_callback(q: CallbackQuery) -> bool {
+    q.data.as_deref().is_some_and(|d| d.starts_with("del:"))
+}
+
+/// دستور /delete؛ با آرگومان مستقیماً جستجو می‌کند
+pub async fn start_delete(bot: Bot, dialogue: MyDialogue, msg: Message, args: String) -> HandlerResult {
+    let client = match client(msg.chat.id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    if args.trim().is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "شناسه، نام یا بارکد محصولی که می‌خواهید حذف کنید را بفرستید.\nبرای انصراف /cancel",
+        )
+        .await?;
+        dialogue.update(State::DeleteFindProduct).await?;
+        return Ok(());
+    }
+
+    show_matches(&bot, &client, msg.chat.id, &args).await?;
+    Ok(())
+}
+
+/// دستور /undo: حذف آخرین محصولی که در همین چت ساخته شده
+pub async fn undo_last_product(bot: Bot, msg: Message) -> HandlerResult {
+    let Some(product_id) = last_created(msg.chat.id.0) else {
+        bot.send_message(msg.chat.id, "محصولی که اخیراً در این گفتگو ساخته باشید پیدا نشد.")
+            .await?;
+        return Ok(());
+    };
+
+    let client = match client(msg.chat.id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+    let product = match client.get_product(product_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("error in reading product {}: {}", product_id, e);
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    bot.send_message(
+        msg.chat.id,
+        format!("آخرین محصول ساخته‌شده:\n{}", product_summary(&product)),
+    )
+    .reply_markup(confirm_keyboard(product.id))
+    .await?;
+    Ok(())
+}
+
+/// دریافت عبارت جستجو بعد از /delete بدون آرگومان
+pub async fn receive_delete_query(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+    let Some(text) = msg.text() else {
+        bot.send_message(msg.chat.id, "شناسه، نام یا بارکد محصول را به صورت متن بفرستید.")
+            .await?;
+        return Ok(());
+    };
+
+    if text.trim().eq_ignore_ascii_case("/cancel") {
+        bot.send_message(msg.chat.id, "حذف محصول کنسل شد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+
+    let client = match client(msg.chat.id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            dialogue.update(State::Start).await?;
+            return Ok(());
+        }
+    };
+    if show_matches(&bot, &client, msg.chat.id, text).await? {
+        // ادامه با دکمه‌ها انجام می‌شود که به وضعیت مکالمه وابسته نیستند
+        dialogue.update(State::Start).await?;
+    }
+    Ok(())
+}
+
+/// همهٔ دکمه‌های `del:*`
+///
+/// - `del:p:{id}` انتخاب از نتایج جستجو
+/// - `del:ask:{id}` / `del:yes:{id}` پرسش و تأیید حذف محصول
+/// - `del:img:{id}` / `del:ia:{id}:{image}` / `del:iy:{id}:{image}` انتخاب، پرسش و تأیید حذف یک تصویر (دکمهٔ 🗑 گالری هم مستقیماً `del:ia` است)
+/// - `del:r:{id}` بازگردانی از سطل
+/// - `del:no` انصراف
+pub async fn delete_callback(bot: Bot, q: CallbackQuery) -> HandlerResult {
+    let (Some(data), Some(message)) = (q.data.as_deref(), q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat.id;
+    let message_id = message.id;
+
+    let parts: Vec<&str> = data.split(':').skip(1).collect();
+    let id = |i: usize| parts.get(i).and_then(|x| x.parse::<u64>().ok());
+
+    let client = match client(chat_id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.send_message(chat_id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    match (parts.first().copied(), id(1), id(2)) {
+        (Some("p"), Some(product_id), _) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            match client.get_product(product_id).await {
+                Ok(product) => {
+                    bot.edit_message_text(chat_id, message_id, product_summary(&product))
+                        .reply_markup(confirm_keyboard(product.id))
+                        .await?;
+                }
+                Err(e) => report(&bot, chat_id, "reading product", e).await?,
+            }
+        }
+        (Some("ask"), Some(product_id), _) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.edit_message_text(
+                chat_id,
+                message_id,
+                format!("⚠️ آیا مطمئنید محصول {} حذف شود؟", product_id),
+            )
+            .reply_markup(yes_no_keyboard(format!("del:yes:{}", product_id)))
+            .await?;
+        }
+        (Some("yes"), Some(product_id), _) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            delete_product(&bot, &client, chat_id, message_id, product_id).await?;
+        }
+        (Some("img"), Some(product_id), _) => {
+            let images = match client.list_product_images(product_id).await {
+                Ok(images) => images,
+                Err(e) => {
+                    bot.answer_callback_query(q.id.clone()).await?;
+                    return report(&bot, chat_id, "listing product images", e).await;
+                }
+            };
+            if images.is_empty() {
+                bot.answer_callback_query(q.id.clone())
+                    .text("این محصول تصویری ندارد.")
+                    .await?;
+                return Ok(());
+            }
+            bot.answer_callback_query(q.id.clone()).await?;
+
+            let mut rows: Vec<Vec<InlineKeyboardButton>> = images
+                .iter()
+                .map(|img| {
+                    let mut label = format!("🖼 تصویر {}", img.id);
+                    if img.default {
+                        label.push_str(" (پیش‌فرض)");
+                    }
+                    vec![InlineKeyboardButton::callback(
+                        label,
+                        format!("del:ia:{}:{}", product_id, img.id),
+                    )]
+                })
+                .collect();
+            rows.push(vec![InlineKeyboardButton::callback("انصراف", "del:no")]);
+
+            let links: Vec<String> = images
+                .iter()
+                .map(|img| format!("{}: {}", img.id, img.image.as_deref().unwrap_or("—")))
+                .collect();
+            bot.edit_message_text(
+                chat_id,
+                message_id,
+                format!("کدام تصویر محصول {} حذف شود؟\n{}", product_id, links.join("\n")),
+            )
+            .reply_markup(InlineKeyboardMarkup::new(rows))
+            .await?;
+        }
+        (Some("ia"), Some(product_id), Some(image_id)) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.edit_message_text(
+                chat_id,
+                message_id,
+                format!("⚠️ آیا مطمئنید تصویر {} از محصول {} حذف شود؟", image_id, product_id),
+            )
+            .reply_markup(yes_no_keyboard(format!("del:iy:{}:{}", product_id, image_id)))
+            .await?;
+        }
+        (Some("iy"), Some(product_id), Some(image_id)) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            match client.delete_product_image(product_id, image_id).await {
+                Ok(()) => {
+                    bot.edit_message_text(
+                        chat_id,
+                        message_id,
+                        format!("🗑 تصویر {} از محصول {} حذف شد.", image_id, product_id),
+                    )
+                    .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+                        InlineKeyboardButton::callback("🖼 تصاویر محصول", format!("gal:l:{}", product_id)),
+                    ]]))
+                    .await?;
+                }
+                Err(e) => report(&bot, chat_id, "deleting product image", e).await?,
+            }
+        }
+        (Some("r"), Some(product_id), _) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            restore_product(&bot, &client, chat_id, message_id, product_id).await?;
+        }
+        _ => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.edit_message_text(chat_id, message_id, "حذف لغو شد.").await?;
+        }
+    }
+    Ok(())
+}
+
+async fn delete_product(
+    bot: &Bot,
+    client: &TorobClient,
+    chat_id: ChatId,
+    message_id: MessageId,
+    product_id: u64,
+) -> HandlerResult {
+    // قبل از حذف، اطلاعات لازم برای بازگردانی خوانده می‌شود؛ نسخهٔ ناقص در سطل گذاشته نمی‌شود
+    let product = match client.get_complete_product(product_id).await {
+        Ok(p) => p,
+        Err(TorobError::InvalidResponse(why)) => {
+            eprintln!("could not read product {} completely before delete: {}", product_id, why);
+            bot.edit_message_text(
+                chat_id,
+                message_id,
+                "❌ همهٔ فیلدهای محصول قابل خواندن نبود و نسخهٔ بازگردانی کامل نمی‌شد؛ محصول حذف نشد.",
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(e) => return report(bot, chat_id, "reading product", e).await,
+    };
+    // بعد از حذف، فایل تصاویر روی سایت نمی‌ماند؛ بایت‌ها، alt و پیش‌فرض همین حالا خوانده می‌شوند
+    let listed = match client.list_product_images(product_id).await {
+        Ok(images) => images,
+        Err(e) => {
+            eprintln!("could not list images of product {} before delete: {}", product_id, e);
+            Vec::new()
+        }
+    };
+    let mut images: Vec<DeletedImage> = Vec::new();
+    for image in &listed {
+        let Some(url) = image.image.as_deref() else {
+            continue;
+        };
+        match client.fetch_image_bytes(url).await {
+            Ok(bytes) => images.push(DeletedImage::new(image, bytes)),
+            Err(e) => eprintln!("could not fetch image {} before delete: {}", url, e),
+        }
+    }
+    let missing = listed.len() - images.len();
+
+    // اول در سطل ذخیره می‌شود تا حذف هیچ‌وقت بدون امکان بازگردانی انجام نشود
+    let name = product.fields.name.clone();
+    if let Err(e) = trash().put(chat_id.0, product, images) {
+        eprintln!("could not put product {} in trash: {}", product_id, e);
+        bot.edit_message_text(
+            chat_id,
+            message_id,
+            "❌ ذخیرهٔ نسخهٔ بازگردانی ممکن نشد؛ محصول حذف نشد.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if let Err(e) = client.delete_product(product_id).await {
+        if let Err(e) = trash().take(chat_id.0, product_id) {
+            eprintln!("could not drop product {} from trash: {}", product_id, e);
+        }
+        return report(bot, chat_id, "deleting product", e).await;
+    }
+    forget_created(chat_id.0, product_id);
+
+    let note = if missing > 0 {
+        format!("\n⚠️ {} تصویر قابل دانلود نبود و بازگردانی نمی‌شود.", missing)
+    } else {
+        String::new()
+    };
+
+    bot.edit_message_text(
+        chat_id,
+        message_id,
+        format!(
+            "🗑 محصول «{}» ({}) حذف شد.\nتا {} دقیقه می‌توانید آن را بازگردانید.{}",
+            name,
+            product_id,
+            trash().ttl().as_secs().div_ceil(60),
+            note
+        ),
+    )
+    .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("♻️ بازگردانی", format!("del:r:{}", product_id)),
+    ]]))
+    .await?;
+    Ok(())
+}
+
+async fn restore_product(
+    bot: &Bot,
+    client: &TorobClient,
+    chat_id: ChatId,
+    message_id: MessageId,
+    product_id: u64,
+) -> HandlerResult {
+    let deleted = match trash().take(chat_id.0, product_id) {
+        Ok(deleted) => deleted,
+        Err(e) => {
+            eprintln!("could not read product {} from trash: {}", product_id, e);
+            None
+        }
+    };
+    let Some(deleted) = deleted else {
+        bot.edit_message_text(
+            chat_id,
+            message_id,
+            "مهلت بازگردانی این محصول تمام شده است.",
+        )
+        .await?;
+        return Ok(());
+    };
+
+    match client.restore_product(&deleted).await {
+        Ok((new_id, images)) => {
+            bot.edit_message_text(
+                chat_id,
+                message_id,
+                format!(
+                    "♻️ محصول «{}» دوباره ساخته شد.\n🆔 شناسهٔ جدید: {}\nتصاویر بازگردانی‌شده: {} از {}",
+                    deleted.product.fields.name,
+                    new_id,
+                    images,
+                    deleted.images.len()
+                ),
+            )
+            .await?;
+        }
+        Err(e) => {
+            // تا پایان مهلت دوباره قابل تلاش است
+            if let Err(e) = trash().put_back(chat_id.0, deleted) {
+                eprintln!("could not put product {} back in trash: {}", product_id, e);
+            }
+            report(bot, chat_id, "restoring product", e).await?;
+        }
+    }
+    Ok(())
+}
+
+/// جستجو؛ یک نتیجه مستقیماً خلاصه و دکمه‌های حذف را نشان می‌دهد. برمی‌گرداند: آیا چیزی پیدا شد
+async fn show_matches(
+    bot: &Bot,
+    client: &TorobClient,
+    chat_id: ChatId,
+    query: &str,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    pick_product(bot, client, chat_id, query, "del:p", Some("del:no"), |product| async move {
+        bot.send_message(chat_id, product_summary(&product))
+            .reply_markup(confirm_keyboard(product.id))
+            .await?;
+        Ok(())
+    })
+    .await
+}
+
+fn product_summary(product: &Product) -> String {
+    let price = product
+        .fields
+        .price
+        .map(|v| format!("{} تومان", v))
+        .unwrap_or_else(|| "بدون قیمت".into());
+    format!(
+        "🆔 {} — {}\nقیمت: {}\nدسته‌بندی: {}",
+        product.id, product.fields.name, price, product.fields.main_category
+    )
+}
+
+/// دکمه‌های حذف محصول، حذف یک تصویر یا انصراف
+fn confirm_keyboard(product_id: u64) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("🗑 حذف محصول", format!("del:ask:{}", product_id)),
+            InlineKeyboardButton::callback("🖼 حذف یک تصویر", format!("del:img:{}", product_id)),
+        ],
+        vec![InlineKeyboardButton::callback("انصراف", "del:no")],
+    ])
+}
+
+fn yes_no_keyboard(yes_data: String) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("✅ بله، حذف شود", yes_data),
+        InlineKeyboardButton::callback("❌ خیر", "del:no"),
+    ]])
+}
@@ -0,0 +1,259 @@
+use crate::telegram_infrastructure::models::state::State;
+use crate::utilities::config::{AppConfig, DialogueBackend};
+use crate::utilities::database::SharedConnection;
+use futures::future::BoxFuture;
+use rusqlite::{OptionalExtension, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use teloxide::dispatching::dialogue::serializer::{Json, Serializer};
+use teloxide::dispatching::dialogue::{ErasedStorage, InMemStorage, RedisStorage, Storage};
+use teloxide::types::ChatId;
+
+/// خطاهای ذخیره‌سازی وضعیت مکالمه در SQLite
+#[derive(Debug)]
+pub enum DialogueStorageError {
+    Database(rusqlite::Error),
+    Serde(serde_json::Error),
+    LockPoisoned,
+    /// حذف مکالمه‌ای که وجود ندارد (مثل `InMemStorage`)
+    DialogueNotFound,
+}
+
+impl fmt::Display for DialogueStorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DialogueStorageError::Database(e) => write!(f, "dialogue database error: {}", e),
+            DialogueStorageError::Serde(e) => write!(f, "dialogue (de)serialization error: {}", e),
+            DialogueStorageError::LockPoisoned => write!(f, "dialogue database lock poisoned"),
+            DialogueStorageError::DialogueNotFound => write!(f, "dialogue not found"),
+        }
+    }
+}
+
+impl std::error::Error for DialogueStorageError {}
+
+impl From<rusqlite::Error> for DialogueStorageError {
+    fn from(e: rusqlite::Error) -> Self {
+        DialogueStorageError::Database(e)
+    }
+}
+
+impl From<serde_json::Error> for DialogueStorageError {
+    fn from(e: serde_json::Error) -> Self {
+        DialogueStorageError::Serde(e)
+    }
+}
+
+/// وضعیتی که جای نسخهٔ ذخیره‌شدهٔ ناخوانا برگردانده می‌شود (مثلاً بعد از تغییر مدل وضعیت)
+///
+/// ربات با دیدن آن به کاربر خبر می‌دهد و مکالمه را از `Start` ادامه می‌دهد.
+pub trait RecoverableState {
+    fn unreadable() -> Self;
+}
+
+impl RecoverableState for State {
+    fn unreadable() -> Self {
+        State::Unreadable
+    }
+}
+
+/// سریالایزر Redis که به جای خطا در هر آپدیت، وضعیت ناخوانا را با `unreadable()` جایگزین می‌کند
+pub struct ResetOnError<S>(pub S);
+
+impl<D, S> Serializer<D> for ResetOnError<S>
+where
+    D: RecoverableState,
+    S: Serializer<D>,
+    S::Error: fmt::Display,
+{
+    type Error = S::Error;
+
+    fn serialize(&self, val: &D) -> Result<Vec<u8>, Self::Error> {
+        self.0.serialize(val)
+    }
+
+    fn deserialize(&self, data: &[u8]) -> Result<D, Self::Error> {
+        match self.0.deserialize(data) {
+            Ok(state) => Ok(state),
+            Err(e) => {
+                eprintln!("resetting unreadable dialogue state: {}", e);
+                Ok(D::unreadable())
+            }
+        }
+    }
+}
+
+/// نگهداری وضعیت مکالمه‌ها در جدول `dialogues` همان پایگاه دادهٔ اعتبارنامه‌ها (به صورت JSON)
+pub struct SqliteDialogueStorage<D> {
+    conn: SharedConnection,
+    _state: PhantomData<fn() -> D>,
+}
+
+impl<D> SqliteDialogueStorage<D> {
+    pub fn new(conn: SharedConnection) -> Arc<Self> {
+        Arc::new(Self {
+            conn,
+            _state: PhantomData,
+        })
+    }
+}
+
+impl<D> Storage<D> for SqliteDialogueStorage<D>
+where
+    D: Serialize + DeserializeOwned + RecoverableState + Send + 'static,
+{
+    type Error = DialogueStorageError;
+
+    fn remove_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let conn = self.conn.lock().map_err(|_| DialogueStorageError::LockPoisoned)?;
+            let deleted = conn.execute("DELETE FROM dialogues WHERE chat_id = ?1", params![chat_id.0])?;
+            if deleted == 0 {
+                return Err(DialogueStorageError::DialogueNotFound);
+            }
+            Ok(())
+        })
+    }
+
+    fn update_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+        dialogue: D,
+    ) -> BoxFuture<'static, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let json = serde_json::to_string(&dialogue)?;
+            let conn = self.conn.lock().map_err(|_| DialogueStorageError::LockPoisoned)?;
+            conn.execute(
+                "INSERT INTO dialogues (chat_id, state) VALUES (?1, ?2)
+                 ON CONFLICT(chat_id) DO UPDATE SET state = excluded.state,
+                                                    updated_at = strftime('%s', 'now')",
+                params![chat_id.0, json],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> BoxFuture<'static, Result<Option<D>, Self::Error>> {
+        Box::pin(async move {
+            let json: Option<String> = {
+                let conn = self.conn.lock().map_err(|_| DialogueStorageError::LockPoisoned)?;
+                conn.query_row(
+                    "SELECT state FROM dialogues WHERE chat_id = ?1",
+                    params![chat_id.0],
+                    |row| row.get(0),
+                )
+                .optional()?
+            };
+
+            match json {
+                Some(json) => match serde_json::from_str::<D>(&json) {
+                    Ok(state) => Ok(Some(state)),
+                    Err(e) => {
+                        // وضعیت قدیمی با مدل فعلی سازگار نیست؛ مکالمه از ابتدا شروع می‌شود
+                        eprintln!("resetting unreadable dialogue of chat {}: {}", chat_id, e);
+                        Ok(Some(D::unreadable()))
+                    }
+                },
+                None => Ok(None),
+            }
+        })
+    }
+}
+
+/// ساخت محل ذخیرهٔ مکالمه‌ها بر اساس `dialogue.backend` در تنظیمات
+///
+/// برای `sqlite` باید اتصال پایگاه داده (همان فایل `storage.database_path`) داده شود.
+pub async fn open_dialogue_storage(
+    config: &AppConfig,
+    conn: Option<SharedConnection>,
+) -> Result<Arc<ErasedStorage<State>>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let storage = match config.dialogue.backend {
+        DialogueBackend::Memory => InMemStorage::<State>::new().erase(),
+        DialogueBackend::Sqlite => {
+            let conn = conn.ok_or("sqlite dialogue storage needs an open database")?;
+            SqliteDialogueStorage::<State>::new(conn).erase()
+        }
+        DialogueBackend::Redis => {
+            let url = config
+                .dialogue
+                .redis_url
+                .as_deref()
+                .ok_or("dialogue.redis_url is required for the redis backend")?;
+            RedisStorage::open(url, ResetOnError(Json)).await?.erase()
+        }
+    };
+    Ok(storage)
+}
+
+#[cfg(test)]
+mod test_dialogue_storage {
+    use super::*;
+    use crate::telegram_infrastructure::models::product_draft::ProductDraft;
+    use crate::utilities::database::open_in_memory;
+
+    #[tokio::test]
+    async fn test_state_survives_reopen() {
+        let conn = open_in_memory().unwrap();
+        let chat = ChatId(42);
+        let mut draft = ProductDraft::default();
+        draft.product.name = "کفش".into();
+        draft.product.price = Some(250000);
+        draft.category_name = Some("پوشاک".into());
+        let state = State::ReceiveProductImage {
+            product_id: 99,
+            draft,
+            batch: Default::default(),
+        };
+
+        SqliteDialogueStorage::<State>::new(conn.clone())
+            .update_dialogue(chat, state)
+            .await
+            .unwrap();
+
+        // یک نمونهٔ جدید روی همان پایگاه داده (مثل بعد از ری‌استارت)
+        let reopened = SqliteDialogueStorage::<State>::new(conn);
+        let restored = reopened.clone().get_dialogue(chat).await.unwrap();
+        assert!(matches!(
+            restored,
+            Some(State::ReceiveProductImage { product_id: 99, .. })
+        ));
+
+        reopened.clone().remove_dialogue(chat).await.unwrap();
+        assert!(matches!(
+            reopened.remove_dialogue(chat).await,
+            Err(DialogueStorageError::DialogueNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_state_resets() {
+        let conn = open_in_memory().unwrap();
+        conn.lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO dialogues (chat_id, state) VALUES (7, '{\"CreateProduct\":{\"step\":\"x\"}}')",
+                [],
+            )
+            .unwrap();
+        let restored = SqliteDialogueStorage::<State>::new(conn)
+            .get_dialogue(ChatId(7))
+            .await
+            .unwrap();
+        assert!(matches!(restored, Some(State::Unreadable)));
+
+        let redis = ResetOnError(Json);
+        let state: State = redis.deserialize(b"{\"Removed\":null}").unwrap();
+        assert!(matches!(state, State::Unreadable));
+        let bytes = redis.serialize(&State::Start).unwrap();
+        assert!(matches!(redis.deserialize(&bytes).unwrap(), State::Start));
+    }
+}
@@ -0,0 +1,305 @@
+use crate::services::category_cache::cached_categories;
+use crate::services::models::product::{Product, ProductField};
+use crate::services::torob_client::TorobClient;
+use crate::services::torob_error::TorobError;
+use crate::telegram_infrastructure::endpoints::{client, HandlerResult, MyDialogue};
+use crate::telegram_infrastructure::models::state::State;
+use crate::telegram_infrastructure::product_picker::pick_product;
+use teloxide::Bot;
+use teloxide::payloads::{EditMessageTextSetters, SendMessageSetters};
+use teloxide::prelude::{CallbackQuery, ChatId, Message};
+use teloxide::requests::Requester;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+/// شروع ویرایش با دستور /edit
+pub async fn start_edit(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+    if let Err(e) = TorobClient::for_chat(&msg.chat.id.0.to_string()) {
+        bot.send_message(msg.chat.id, e.user_message()).await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        msg.chat.id,
+        "شناسه، نام یا بارکد محصولی که می‌خواهید ویرایش کنید را بفرستید.\nبرای انصراف /cancel",
+    )
+    .await?;
+    dialogue.update(State::EditFindProduct).await?;
+    Ok(())
+}
+
+/// دریافت عبارت جستجو و نمایش محصول(ها)
+pub async fn receive_edit_query(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+    let Some(text) = msg.text() else {
+        bot.send_message(msg.chat.id, "شناسه، نام یا بارکد محصول را به صورت متن بفرستید.")
+            .await?;
+        return Ok(());
+    };
+
+    if text.trim().eq_ignore_ascii_case("/cancel") {
+        bot.send_message(msg.chat.id, "ویرایش محصول کنسل شد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+
+    let client = match client(msg.chat.id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    let (bot, chat_id) = (&bot, msg.chat.id);
+    pick_product(bot, &client, chat_id, text, "edit:p", None, |product| async move {
+        send_card(bot, chat_id, &product).await?;
+        dialogue
+            .update(State::EditChooseField {
+                product_id: product.id,
+            })
+            .await?;
+        Ok(())
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// انتخاب محصول از بین نتایج جستجو
+pub async fn pick_product_callback(bot: Bot, dialogue: MyDialogue, q: CallbackQuery) -> HandlerResult {
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let Some(product_id) = q
+        .data
+        .as_deref()
+        .and_then(|d| d.strip_prefix("edit:p:"))
+        .and_then(|id| id.parse::<u64>().ok())
+    else {
+        return Ok(());
+    };
+
+    let chat_id = dialogue.chat_id();
+    let client = match client(chat_id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(chat_id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    let product = match client.get_product(product_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("error in reading product {}: {}", product_id, e);
+            bot.send_message(chat_id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    match &q.message {
+        Some(m) => {
+            bot.edit_message_text(chat_id, m.id, product_card(&product))
+                .reply_markup(edit_keyboard())
+                .await?;
+        }
+        None => send_card(&bot, chat_id, &product).await?,
+    }
+    dialogue.update(State::EditChooseField { product_id }).await?;
+    Ok(())
+}
+
+/// انتخاب فیلد برای ویرایش یا پایان ویرایش
+pub async fn choose_field_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    product_id: u64,
+) -> HandlerResult {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let chat_id = dialogue.chat_id();
+
+    match q.data.as_deref() {
+        Some("edit:x") => {
+            if let Some(m) = &q.message {
+                bot.edit_message_reply_markup(chat_id, m.id).await?;
+            }
+            bot.send_message(chat_id, "✅ ویرایش تمام شد. برای شروع دوباره /start")
+                .await?;
+            dialogue.update(State::Start).await?;
+        }
+        Some(data) => {
+            let Some(field) = data.strip_prefix("edit:f:").and_then(ProductField::from_key) else {
+                return Ok(());
+            };
+            bot.send_message(chat_id, field.prompt()).await?;
+            dialogue
+                .update(State::EditReceiveValue { product_id, field })
+                .await?;
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// پیام متنی وقتی منتظر انتخاب فیلد با دکمه هستیم
+pub async fn receive_edit_choice_text(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+    if msg.text().is_some_and(|t| t.trim().eq_ignore_ascii_case("/cancel")) {
+        bot.send_message(msg.chat.id, "ویرایش محصول کنسل شد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+
+    bot.send_message(
+        msg.chat.id,
+        "فیلد موردنظر را با دکمه‌های زیر کارت محصول انتخاب کنید، یا /cancel بفرستید.",
+    )
+    .await?;
+    Ok(())
+}
+
+/// دریافت مقدار جدید، اعتبارسنجی با تعریف فیلدهای `ProductCreate` و ارسال PATCH
+pub async fn receive_edit_value(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    product_field: (u64, ProductField),
+) -> HandlerResult {
+    let (product_id, field) = product_field;
+
+    let Some(text) = msg.text() else {
+        bot.send_message(msg.chat.id, field.prompt()).await?;
+        return Ok(());
+    };
+
+    if text.trim().eq_ignore_ascii_case("/cancel") {
+        bot.send_message(msg.chat.id, "ویرایش محصول کنسل شد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+
+    let client = match client(msg.chat.id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            dialogue.update(State::Start).await?;
+            return Ok(());
+        }
+    };
+
+    let mut product = match client.get_product(product_id).await {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("error in reading product {}: {}", product_id, e);
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            dialogue.update(State::Start).await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(why) = field.apply(&mut product.fields, text) {
+        bot.send_message(msg.chat.id, why).await?;
+        return Ok(());
+    }
+
+    if field == ProductField::OtherCategories
+        && let Some(ids) = product.fields.other_categories.as_mut()
+    {
+        let cats = match cached_categories(&msg.chat.id.0.to_string()).await {
+            Ok(cats) => cats,
+            Err(e) => {
+                bot.send_message(msg.chat.id, e.user_message()).await?;
+                return Ok(());
+            }
+        };
+        if let Some(bad) = ids
+            .iter()
+            .find(|id| !cats.iter().any(|c| c.id == **id && c.available))
+        {
+            bot.send_message(msg.chat.id, format!("دسته‌بندی {} وجود ندارد یا فعال نیست؛ دوباره بفرستید.", bad))
+                .await?;
+            return Ok(());
+        }
+        let main = product.fields.main_category;
+        ids.retain(|id| *id != main);
+    }
+
+    if field == ProductField::MainCategory {
+        let cats = match cached_categories(&msg.chat.id.0.to_string()).await {
+            Ok(cats) => cats,
+            Err(e) => {
+                bot.send_message(msg.chat.id, e.user_message()).await?;
+                return Ok(());
+            }
+        };
+        match cats.iter().find(|c| c.id == product.fields.main_category) {
+            Some(c) if c.available => {}
+            Some(_) => {
+                bot.send_message(msg.chat.id, "این دسته‌بندی فعال نیست؛ شناسهٔ دیگری بفرستید.")
+                    .await?;
+                return Ok(());
+            }
+            None => {
+                bot.send_message(msg.chat.id, "چنین دسته‌بندی‌ای وجود ندارد؛ دوباره تلاش کنید.")
+                    .await?;
+                return Ok(());
+            }
+        }
+    }
+
+    let updated = match client
+        .update_product(product_id, &field.patch_body(&product.fields))
+        .await
+    {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("error in updating product {}: {}", product_id, e);
+            // خطای اعتبارسنجی سایت: کاربر مقدار دیگری بفرستد
+            let retry = matches!(e, TorobError::Validation { .. });
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            if !retry {
+                dialogue.update(State::EditChooseField { product_id }).await?;
+            }
+            return Ok(());
+        }
+    };
+
+    bot.send_message(msg.chat.id, format!("✅ {} به‌روزرسانی شد.", field.label()))
+        .await?;
+    send_card(&bot, msg.chat.id, &updated).await?;
+    dialogue.update(State::EditChooseField { product_id }).await?;
+    Ok(())
+}
+
+async fn send_card(bot: &Bot, chat_id: ChatId, product: &Product) -> HandlerResult {
+    bot.send_message(chat_id, product_card(product))
+        .reply_markup(edit_keyboard())
+        .await?;
+    Ok(())
+}
+
+/// متن کارت محصول با مقدار فعلی همهٔ فیلدهای قابل ویرایش
+pub fn product_card(product: &Product) -> String {
+    let mut lines = vec![
+        format!("🆔 محصول {}", product.id),
+        "─────────────────────".to_string(),
+    ];
+    for field in ProductField::ALL {
+        let value = field.value_text(&product.fields).unwrap_or_else(|| "—".into());
+        lines.push(format!("{}: {}", field.label(), value));
+    }
+    lines.push("\nفیلدی را که می‌خواهید تغییر دهید انتخاب کنید:".into());
+    lines.join("\n")
+}
+
+/// دکمه‌های انتخاب فیلد (دو ستونه) و دکمهٔ پایان
+pub fn edit_keyboard() -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = ProductField::ALL
+        .chunks(2)
+        .map(|pair| {
+            pair.iter()
+                .map(|f| InlineKeyboardButton::callback(f.label(), format!("edit:f:{}", f.key())))
+                .collect()
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback("✅ پایان ویرایش", "edit:x")]);
+    InlineKeyboardMarkup::new(rows)
+}
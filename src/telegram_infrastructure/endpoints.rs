@@ -1,19 +1,21 @@
-use crate::services::models::category::Category;
+use crate::services::category_cache::{category_cache, refresh_categories};
+use crate::telegram_infrastructure::models::product_draft::ProductDraft;
+use crate::telegram_infrastructure::product_wizard::product_wizard;
+use crate::services::panel_probe::{probe_site, probe_token};
+use crate::services::torob_client::TorobClient;
+use crate::services::torob_error::TorobError;
 use crate::telegram_infrastructure::models::command::Command;
 use crate::telegram_infrastructure::models::state::State;
-use crate::telegram_infrastructure::models::state::State::ReceiveProductName;
-use crate::utilities::session::remove_session_by_chat;
-use crate::utilities::site::{get_site, remove_site};
+use crate::utilities::config::config;
+use crate::utilities::site::{get_site, remove_site, set_site};
 use crate::utilities::token::{get_token, remove_token, set_token};
 use teloxide::Bot;
-use teloxide::dispatching::dialogue::InMemStorage;
-use teloxide::net::Download;
-use teloxide::payloads::SendPhotoSetters;
-use teloxide::prelude::{ChatId, Dialogue, Message};
+use teloxide::dispatching::dialogue::ErasedStorage;
+use teloxide::payloads::AnswerCallbackQuerySetters;
+use teloxide::prelude::{CallbackQuery, ChatId, Dialogue, Message};
 use teloxide::requests::Requester;
-use teloxide::types::InputFile;
 
-type MyDialogue = Dialogue<State, InMemStorage<State>>;
+pub type MyDialogue = Dialogue<State, ErasedStorage<State>>;
 pub type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
 
 /// کاربر اگر ربات را استارت کند اتفاقات این تابع ران میشود
@@ -22,7 +24,7 @@ pub async fn start(bot: Bot, dialogue: MyDialogue, msg: Message, cmd: Command) -
         Command::Start => {
             bot.send_message(
                 msg.chat.id,
-                "سلام! برای ثبت محصول جدید /registerandcreatenewproduct را بفرست.\nبرای حذف اطلاعات قبلی و تغییر توکن از /changetoken استفاده کنید.\nهر زمان با /cancel انصراف بده.",
+                "سلام! برای ثبت محصول جدید /registerandcreatenewproduct را بفرست.\nبرای دیدن محصولات /products، برای ویرایش /edit و برای حذف /delete را بفرست.\nبرای دیدن و مدیریت تصاویر یک محصول /images را بفرست.\nاگر آخرین محصول اشتباه ساخته شد /undo را بزن.\nبرای ساخت دسته /newcategory و برای تغییر نام یا فعال/غیرفعال کردن دسته‌ها /categories را بفرست.\nبعد از تغییر دسته‌بندی‌ها در پنل /refreshcategories را بفرست.\nبرای حذف اطلاعات قبلی و تغییر توکن از /changetoken استفاده کنید.\nهر زمان با /cancel انصراف بده.",
             )
                 .await?;
             dialogue.update(State::Start).await?;
@@ -37,14 +39,23 @@ pub async fn start(bot: Bot, dialogue: MyDialogue, msg: Message, cmd: Command) -
             let token = get_token(&chat_id_telegram);
 
             if site.is_some() && token.is_some() {
-                message = "نام محصول را وارد کنید";
-                start_state = State::ReceiveProductName;
+                product_wizard()
+                    .start(&bot, dialogue, ProductDraft::default())
+                    .await?;
+                return Ok(());
             } else if site.is_some() && token.is_none() {
                 message = "توکن خود را وارد کنید";
                 start_state = State::ReceiveToken;
+            } else if let Some(default_site) = &config().default_panel_url {
+                // آدرس پنل از تنظیمات می‌آید؛ فقط توکن لازم است
+                set_site(chat_id_telegram.clone(), default_site.clone());
+                category_cache().invalidate(&chat_id_telegram);
+                message = "توکن خود را وارد کنید";
+                start_state = State::ReceiveToken;
             } else {
                 remove_token(&chat_id_telegram);
-                remove_token(&chat_id_telegram);
+                remove_site(&chat_id_telegram);
+                category_cache().invalidate(&chat_id_telegram);
             }
 
             bot.send_message(msg.chat.id, message).await?;
@@ -55,11 +66,54 @@ pub async fn start(bot: Bot, dialogue: MyDialogue, msg: Message, cmd: Command) -
                 .await?;
             dialogue.update(State::Start).await?;
         }
+        Command::Edit => {
+            crate::telegram_infrastructure::edit_endpoints::start_edit(bot, dialogue, msg).await?;
+        }
+        Command::Products(args) => {
+            crate::telegram_infrastructure::list_endpoints::start_listing(bot, msg, args).await?;
+        }
+        Command::Delete(args) => {
+            crate::telegram_infrastructure::delete_endpoints::start_delete(bot, dialogue, msg, args)
+                .await?;
+        }
+        Command::Images(args) => {
+            crate::telegram_infrastructure::gallery_endpoints::start_gallery(bot, dialogue, msg, args)
+                .await?;
+        }
+        Command::Undo => {
+            crate::telegram_infrastructure::delete_endpoints::undo_last_product(bot, msg).await?;
+        }
+        Command::RefreshCategories => {
+            let chat_id_telegram = msg.chat.id.0.to_string();
+            match refresh_categories(&chat_id_telegram).await {
+                Ok(cats) => {
+                    bot.send_message(msg.chat.id, format!("✅ {} دسته‌بندی از پنل خوانده شد.", cats.len()))
+                        .await?;
+                }
+                Err(e) => {
+                    eprintln!("error in refreshing categories: {}", e);
+                    bot.send_message(
+                        msg.chat.id,
+                        format!("❌ خطا در دریافت دسته‌بندی‌ها:\n{}", e.user_message()),
+                    )
+                    .await?;
+                }
+            }
+        }
+        Command::NewCategory => {
+            crate::telegram_infrastructure::category_endpoints::start_new_category(bot, dialogue, msg)
+                .await?;
+        }
+        Command::Categories => {
+            crate::telegram_infrastructure::category_endpoints::start_manage(bot, msg).await?;
+        }
         Command::ChangeToken => {
             let chat_id_telegram = msg.chat.id.0.to_string();
 
             remove_token(&chat_id_telegram);
             remove_site(&chat_id_telegram);
+            // دسته‌بندی‌های کش‌شده مال پنل/توکن قبلی‌اند
+            category_cache().invalidate(&chat_id_telegram);
 
             bot.send_message(
                 msg.chat.id,
@@ -72,6 +126,57 @@ pub async fn start(bot: Bot, dialogue: MyDialogue, msg: Message, cmd: Command) -
     Ok(())
 }
 
+/// دکمهٔ یک پیام قدیمی که با وضعیت فعلی مکالمه نمی‌خواند
+pub async fn stale_callback(bot: Bot, q: CallbackQuery) -> HandlerResult {
+    bot.answer_callback_query(q.id)
+        .text("این دکمه دیگر فعال نیست.")
+        .await?;
+    Ok(())
+}
+
+/// وضعیت قبلی از SQLite/Redis خوانده نشد (مثلاً بعد از به‌روزرسانی ربات)
+pub async fn unreadable_state(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, UNREADABLE_STATE_TEXT).await?;
+    dialogue.update(State::Start).await?;
+    Ok(())
+}
+
+pub async fn unreadable_state_callback(bot: Bot, dialogue: MyDialogue, q: CallbackQuery) -> HandlerResult {
+    bot.answer_callback_query(q.id.clone()).await?;
+    bot.send_message(dialogue.chat_id(), UNREADABLE_STATE_TEXT).await?;
+    dialogue.update(State::Start).await?;
+    Ok(())
+}
+
+const UNREADABLE_STATE_TEXT: &str =
+    "⚠️ ادامهٔ کار قبلی شما قابل بازیابی نبود و مکالمه از ابتدا شروع شد. لطفاً دستور را دوباره بفرستید.";
+
+/// دکمه‌هایی که گفتگوی تازه‌ای شروع می‌کنند فقط در `State::Start` اجرا می‌شوند تا کار نیمه‌تمام
+/// کاربر (مثلاً ساخت محصول) بی‌صدا از دست نرود. اگر کاربر مشغول باشد callback با هشدار جواب
+/// داده می‌شود و `false` برمی‌گردد؛ در غیر این صورت جواب دادن callback با خود handler است.
+pub async fn ensure_idle(bot: &Bot, dialogue: &MyDialogue, q: &CallbackQuery) -> Result<bool, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    if matches!(dialogue.get().await?, None | Some(State::Start)) {
+        return Ok(true);
+    }
+    bot.answer_callback_query(q.id.clone())
+        .text("اول کار فعلی را تمام کنید یا /cancel بفرستید.")
+        .show_alert(true)
+        .await?;
+    Ok(false)
+}
+
+/// کلاینت پنل همین چت (بدون آدرس یا توکن ذخیره‌شده خطا می‌دهد)
+pub fn client(chat_id: ChatId) -> Result<TorobClient, TorobError> {
+    TorobClient::for_chat(&chat_id.0.to_string())
+}
+
+/// ثبت خطای پنل در لاگ و فرستادن پیام قابل فهم آن به کاربر
+pub async fn report(bot: &Bot, chat_id: ChatId, action: &str, e: TorobError) -> HandlerResult {
+    eprintln!("error in {}: {}", action, e);
+    bot.send_message(chat_id, e.user_message()).await?;
+    Ok(())
+}
+
 ///دریافت آدرس پنل کاربر
 pub async fn receive_website(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
     let Some(text) = msg.text() else {
@@ -98,24 +203,29 @@ pub async fn receive_website(bot: Bot, dialogue: MyDialogue, msg: Message) -> Ha
         return Ok(());
     }
 
-    if website.starts_with("https") == false {
-        bot.send_message(
-            msg.chat.id,
-            "آدرس سایت شما نامعتبر میباشد ادرس شما باید با https آغاز شود",
-        )
+    bot.send_message(msg.chat.id, "⏳ در حال بررسی آدرس پنل...")
         .await?;
 
-        return Ok(());
-    }
+    // آدرس یکسان‌سازی و پنل بررسی می‌شود؛ در صورت خطا در همین مرحله می‌مانیم
+    let site = match probe_site(&website).await {
+        Ok(site) => site,
+        Err(e) => {
+            eprintln!("panel probe failed for chat {} ({}): {}", msg.chat.id, website, e);
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
 
     let chat_id_telegram: ChatId = msg.chat.id;
 
-    crate::utilities::site::set_site(
-        chat_id_telegram.0.to_string(),
-        website.trim_end_matches('/').to_string(),
-    );
+    set_site(chat_id_telegram.0.to_string(), site.clone());
+    category_cache().invalidate(&chat_id_telegram.0.to_string());
 
-    bot.send_message(msg.chat.id, "لطفا توکن خود را وارد کنید").await?;
+    bot.send_message(
+        msg.chat.id,
+        format!("✅ پنل {} تأیید شد.\nلطفا توکن خود را وارد کنید", site),
+    )
+    .await?;
     dialogue.update(State::ReceiveToken).await?;
 
     Ok(())
@@ -146,11 +256,37 @@ pub async fn receive_token(bot: Bot, dialogue: MyDialogue, msg: Message) -> Hand
 
     let chat_id = msg.chat.id.0.to_string();
 
-    set_token(chat_id, text.trim().to_string());
+    let Some(site) = get_site(&chat_id) else {
+        bot.send_message(msg.chat.id, "ابتدا آدرس پنل خود را وارد کنید")
+            .await?;
+        dialogue.update(State::ReceiveWebSite).await?;
+        return Ok(());
+    };
+
+    bot.send_message(msg.chat.id, "⏳ در حال بررسی توکن...")
+        .await?;
+
+    // کلید با یک درخواست واقعی به پنل سنجیده می‌شود؛ کلید ردشده ذخیره نمی‌شود
+    if let Err(e) = probe_token(&site, &user_name).await {
+        eprintln!("token probe failed for chat {}: {}", chat_id, e);
+        // کاربر همین حالا در مرحلهٔ توکن است؛ /changetoken اینجا بی‌معنی است
+        let text = match e {
+            TorobError::Unauthorized { .. } => {
+                "❌ این Api-Key توسط پنل پذیرفته نشد؛ لطفاً Api-Key درست را دوباره ارسال کنید.".to_string()
+            }
+            e => e.user_message(),
+        };
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    set_token(chat_id.clone(), user_name);
+    category_cache().invalidate(&chat_id);
 
-    bot.send_message(msg.chat.id, "نام محصول را وارد کنید")
+    bot.send_message(msg.chat.id, "✅ توکن تأیید شد.").await?;
+    product_wizard()
+        .start(&bot, dialogue, ProductDraft::default())
         .await?;
-    dialogue.update(State::ReceiveProductName).await?;
 
     Ok(())
 }
@@ -185,8 +321,6 @@ pub async fn receive_password(
 
     let chat_id_telegram = msg.chat.id.0.to_string();
 
-    println!("chat_id_telegram = {}", chat_id_telegram);
-
     let search_value_by_key = crate::utilities::site::get_site(&chat_id_telegram);
 
     match &search_value_by_key {
@@ -199,31 +333,17 @@ pub async fn receive_password(
             )
             .await;
 
-            println!(
-                "login result = {} with web: {}, user_name: {} & password: {}",
-                result, value, user_name, password
-            );
-
-            let result_bool = match result {
-                "ok" => true,
-                _ => false,
-            };
-
-            if result_bool == false {
+            if result != "ok" {
                 bot.send_message(msg.chat.id, "خطا در ورود به سامانه، لطفا از اول آدرس دقیق سامانه خود و همینطور نام کاربری و رمز عبور خود را مجددا ارسال کنید").await?;
                 dialogue.update(State::Start).await?;
                 return Ok(());
             }
 
-            bot.send_message(msg.chat.id, "نام محصول را وارد کنید")
+            product_wizard()
+                .start(&bot, dialogue, ProductDraft::default())
                 .await?;
-            dialogue.update(State::ReceiveProductName).await?;
         }
         None => {
-            println!(
-                "web site value for this chat id {}, not found.",
-                chat_id_telegram
-            );
             bot.send_message(
                 msg.chat.id,
                 "آدرس سایت شما یافت نشد لطفا آدرس سایت خود را وارد کنید",
@@ -236,362 +356,3 @@ pub async fn receive_password(
 
     Ok(())
 }
-
-/// دریافت نام محصول در ربات
-pub async fn receive_name(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
-    let Some(text) = msg.text() else {
-        bot.send_message(msg.chat.id, "لطفاً نام محصول را به صورت متن بفرستید.")
-            .await?;
-        return Ok(());
-    };
-
-    if text.trim().eq_ignore_ascii_case("/cancel") {
-        bot.send_message(msg.chat.id, "روند ایجاد محصول کنسل شد.")
-            .await?;
-        dialogue.update(State::Start).await?;
-        return Ok(());
-    }
-
-    let name: String = text.trim().to_string();
-
-    if name.is_empty() {
-        bot.send_message(
-            msg.chat.id,
-            "نام خالی است؛ لطفاً دوباره نام محصول را وارد کنید.",
-        )
-        .await?;
-        return Ok(());
-    }
-
-    bot.send_message(msg.chat.id, "قیمت محصول را وارد کنید (فقط عدد).")
-        .await?;
-
-    dialogue.update(State::ReceivePrice { name }).await?;
-
-    Ok(())
-}
-
-pub async fn receive_price(
-    bot: Bot,
-    dialogue: MyDialogue,
-    msg: Message,
-    name: String,
-) -> HandlerResult {
-    let Some(text) = msg.text() else {
-        bot.send_message(msg.chat.id, "لطفاً قیمت را به صورت عدد وارد کنید.")
-            .await?;
-        return Ok(());
-    };
-
-    if text.trim().eq_ignore_ascii_case("/cancel") {
-        bot.send_message(msg.chat.id, "روند ایجاد محصول کنسل شد.")
-            .await?;
-
-        dialogue.update(State::Start).await?;
-
-        return Ok(());
-    }
-
-    let Some(price) = parse_int(text) else {
-        bot.send_message(
-            msg.chat.id,
-            "قیمت نامعتبر است؛ فقط عدد وارد کنید (مثلاً 250000).",
-        )
-        .await?;
-
-        return Ok(());
-    };
-
-    let chat_id = msg.chat.id.0.to_string();
-
-    // ⬇️ گرفتن و نمایش دسته‌بندی‌ها
-    let cats: Vec<Category> =
-        match crate::services::category_service::fetch_categories_from_service(&chat_id).await {
-            Ok(cats) => cats,
-            Err(err) => {
-                bot.send_message(msg.chat.id, err.to_string()).await?;
-                eprintln!("error in fetching categories: {}", err.to_string());
-                return Err(err.into());
-            }
-        };
-
-    if cats.is_empty() {
-        bot.send_message(msg.chat.id, "هیچ دسته‌ بندی‌ ای یافت نشد.")
-            .await?;
-        dialogue.update(State::Start).await?;
-        return Ok(());
-    }
-
-    let txt = categories_to_text(&cats);
-
-    // اگر خیلی بلند بود، تلگرام محدودیت 4096 کاراکتر دارد؛
-    // برای سادگی فعلاً یک پیام می‌فرستیم:
-    // Replace the single message send with this implementation
-    let max_length = 4000; // Slightly less than Telegram's 4096 limit
-    let chunks = txt
-        .chars()
-        .collect::<Vec<char>>()
-        .chunks(max_length)
-        .map(|c| c.iter().collect::<String>())
-        .collect::<Vec<String>>();
-
-    for chunk in chunks {
-        bot.send_message(msg.chat.id, chunk).await?;
-    }
-    bot.send_message(msg.chat.id, "شناسه‌ی دسته‌بندی موردنظر را ارسال کنید.")
-        .await?;
-
-    dialogue
-        .update(State::ReceiveCategoryId { name, price })
-        .await?;
-
-    Ok(())
-}
-
-// کمک‌تابع: پارس عدد صحیح (اجازه‌ی ویرگول/فاصله می‌دهد)
-pub fn parse_int(s: &str) -> Option<i64> {
-    let cleaned: String = s.chars().filter(|c| c.is_ascii_digit()).collect();
-    if cleaned.is_empty() {
-        None
-    } else {
-        cleaned.parse::<i64>().ok()
-    }
-}
-
-pub fn parse_u64(s: &str) -> Option<u64> {
-    let cleaned: String = s.chars().filter(|c| c.is_ascii_digit()).collect();
-    if cleaned.is_empty() {
-        None
-    } else {
-        cleaned.parse::<u64>().ok()
-    }
-}
-
-/// لیست را به متن ساده تبدیل می‌کند
-pub fn categories_to_text(cats: &[Category]) -> String {
-    let mut lines: Vec<String> = Vec::new();
-    lines.push("لطفاً شناسه‌ی دسته‌بندی را وارد کنید:\n".into());
-    for c in cats {
-        let parent = c
-            .parent
-            .map(|p| p.to_string())
-            .unwrap_or_else(|| "Root".into());
-        let av = if c.available { "✅" } else { "⛔️" };
-        lines.push(format!(
-            "{}  {:>4} — {}  (parent: {})",
-            av, c.id, c.name, parent
-        ));
-    }
-    lines.join("\n")
-}
-
-pub fn to_u64(n: i64) -> Option<u64> {
-    if n >= 0 { Some(n as u64) } else { None }
-}
-
-/// دریافت شناسه دسته بندی در ربات
-pub async fn receive_category_id(
-    bot: Bot,
-    dialogue: MyDialogue,
-    msg: Message,
-    name_price: (String, i64),
-) -> HandlerResult {
-    let (name, price) = name_price;
-
-    let Some(text) = msg.text() else {
-        bot.send_message(msg.chat.id, "شناسه‌ی دسته‌بندی را به صورت عدد وارد کنید.")
-            .await?;
-        return Ok(());
-    };
-
-    if text.trim().eq_ignore_ascii_case("/cancel") {
-        bot.send_message(msg.chat.id, "روند ایجاد محصول کنسل شد.")
-            .await?;
-        dialogue.update(State::Start).await?;
-        return Ok(());
-    }
-
-    let Some(cat_id) = text.trim().parse::<u64>().ok() else {
-        bot.send_message(msg.chat.id, "شناسه نامعتبر است؛ فقط عدد بفرستید.")
-            .await?;
-        return Ok(());
-    };
-
-    let chat_id: String = msg.chat.id.0.to_string();
-
-    // گرفتن تازه‌ترین لیست دسته‌بندی‌ها (یا اگر کش داری، از همان استفاده کن)
-    let cats: Vec<Category> =
-        match crate::services::category_service::fetch_categories_from_service(&chat_id).await {
-            Ok(v) => v,
-            Err(e) => {
-                bot.send_message(msg.chat.id, format!("خطا در دریافت دسته‌بندی‌ها: {e}"))
-                    .await?;
-                dialogue.update(State::Start).await?;
-                return Ok(());
-            }
-        };
-
-    let Some(cat) = cats.iter().find(|c| c.id == cat_id) else {
-        bot.send_message(
-            msg.chat.id,
-            "چنین شناسه‌ای در دسته‌بندی‌ها وجود ندارد؛ دوباره تلاش کنید.",
-        )
-        .await?;
-        return Ok(());
-    };
-
-    if cat.available == false {
-        bot.send_message(
-            msg.chat.id,
-            "این دسته‌بندی فعال نیست؛ شناسه‌ی دیگری انتخاب کنید.",
-        )
-        .await?;
-        return Ok(());
-    }
-
-    // آماده‌سازی مدل ایجاد محصول با کمترین داده
-    let price_u64 = match to_u64(price) {
-        Some(v) => v,
-        None => {
-            bot.send_message(msg.chat.id, "قیمت منفی معتبر نیست؛ فرایند متوقف شد.")
-                .await?;
-            dialogue.update(State::Start).await?;
-            return Ok(());
-        }
-    };
-
-    let mut product = crate::services::models::product::ProductCreate::new(name.clone(), cat.id);
-    // اگر نوع فیلدها در ProductCreate، i64 است، همین دو خط را به Some(price) / Some(stock) تغییر بده.
-    product.price = Some(price_u64);
-    // product.stock = Some(stock_u64);
-
-    // let chat_id = msg.chat.id.0.to_string();
-
-    // فراخوانی سرویس ایجاد محصول
-    let product_id = match crate::services::product_service::create_product(&product, chat_id).await
-    {
-        Ok(id) => id,
-        Err(e) => {
-            bot.send_message(msg.chat.id, format!("❌ خطا در ایجاد محصول: {e}"))
-                .await?;
-            dialogue.update(State::Start).await?;
-            return Ok(());
-        }
-    };
-
-    let summary = format!(
-        "✅ محصول با موفقیت ایجاد شد.\n\
-         ─────────────────────\n\
-         نام: {}\n\
-         قیمت: {}\n\
-         دسته‌بندی: {} (id: {})\n\
-         🆔 شناسه محصول: {}\
-         \n برای ثبت محصول بعدی روی /start  کلیک کنید",
-        name, price, cat.name, cat.id, product_id
-    );
-
-    bot.send_message(msg.chat.id, summary).await?;
-
-    // پیام نهایی به کاربر
-    let summary = format!("تصویر مربوط به این محصول را آپلود کنید");
-
-    bot.send_message(msg.chat.id, summary).await?;
-
-    dialogue
-        .update(State::ReceiveProductImage {
-            name,
-            price,
-            category_id: cat.id,
-            category_name: cat.name.clone(),
-            product_id,
-        })
-        .await?;
-
-    Ok(())
-}
-
-/// دریافت تصویر پروفایل در ربات
-pub async fn receive_product_image(
-    bot: Bot,
-    dialogue: MyDialogue,
-    msg: Message,
-    payload: (String, i64, u64, String, u64), // ← تاپل تخت مطابق Available types
-) -> HandlerResult {
-    let (name, price, category_id, category_name, product_id) = payload;
-
-    // بررسی اینکه آیا پیام حاوی تصویر است
-    let Some(photo) = msg.photo() else {
-        bot.send_message(msg.chat.id, "لطفاً یک تصویر ارسال کنید.")
-            .await?;
-        return Ok(());
-    };
-
-    let largest_photo = photo.iter().last().unwrap();
-    let file_id = largest_photo.file.id.clone();
-    let file = bot.get_file(&file_id).await?;
-    let file_path = &file.path;
-
-    let chat_id = msg.chat.id.0.to_string();
-
-    if file.size > 2 * 1024 * 1024 {
-        bot.send_message(msg.chat.id, "❌ حجم تصویر باید کمتر از 2 مگابایت باشد.").await?;
-        return Ok(());
-    }
-
-    // بررسی پسوند مجاز
-    let allowed_exts = ["jpg", "jpeg", "png", "gif", "webp"];
-    let filename = file_path.rsplit('/').next().unwrap_or("image.jpg");
-    let ext_ok = filename
-        .rsplit('.')
-        .next()
-        .map(|ext| allowed_exts.contains(&ext.to_lowercase().as_str()))
-        .unwrap_or(false);
-
-    if !ext_ok {
-        bot.send_message(
-            msg.chat.id,
-            format!("❌ فرمت فایل پشتیبانی نمی‌شود. فرمت‌های مجاز: {:?}", allowed_exts),
-        )
-            .await?;
-        return Ok(());
-    }
-
-    // ارسال تصویر به مقصد (سایت)
-    // دانلود بایت‌ها از تلگرام
-    let mut bytes: Vec<u8> = Vec::new();
-    bot.download_file(&file_path, &mut bytes).await?;
-
-    // یک نام فایل مناسب (از انتهای مسیر تلگرام)
-    let filename = file_path.rsplit('/').next().unwrap_or("image.jpg");
-
-    // آپلود به بک‌اند
-    crate::services::product_image_service::upload_product_image_file(
-        chat_id, product_id, filename, bytes,
-    )
-    .await?;
-
-    // پیام نهایی به کاربر
-    let summary = format!(
-        "✅ محصول با موفقیت ایجاد شد.\n\
-         ─────────────────────\n\
-         نام: {}\n\
-         قیمت: {}\n\
-         دسته‌بندی: {} (id: {})\n\
-         🆔 شناسه محصول: {}\
-         \n برای ثبت محصول بعدی روی /start  کلیک کنید",
-        name, price, category_name, category_id, product_id
-    );
-
-    let caption = summary;
-
-    bot.send_photo(msg.chat.id, InputFile::file_id(file_id.clone()))
-        .caption(caption)
-        .await?;
-
-    let chat_id = msg.chat.id.0.to_string();
-
-    // پایان جریان
-    dialogue.update(State::Start).await?;
-    Ok(())
-}
-
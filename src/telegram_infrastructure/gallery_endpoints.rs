@@ -0,0 +1,477 @@
+use crate::services::models::product_image::{ProductImage, ProductImageCreate};
+use crate::services::torob_client::TorobClient;
+use crate::telegram_infrastructure::endpoints::{client, report, ensure_idle, HandlerResult, MyDialogue};
+use crate::telegram_infrastructure::image_endpoints::{parse_image_text, send_media, upload_image, GALLERY_CHUNK};
+use crate::telegram_infrastructure::models::state::State;
+use crate::telegram_infrastructure::product_picker::pick_product;
+use crate::utilities::image_format::ImageFormat;
+use serde_json::json;
+use teloxide::Bot;
+use teloxide::payloads::{AnswerCallbackQuerySetters, EditMessageTextSetters, SendMessageSetters};
+use teloxide::prelude::{CallbackQuery, ChatId, Message};
+use teloxide::requests::Requester;
+use teloxide::types::{
+    InlineKeyboardButton, InlineKeyboardMarkup, InputFile, InputMedia, InputMediaPhoto, MessageId,
+};
+
+/// آیا این callback مربوط به گالری تصاویر است (مستقل از وضعیت مکالمه)
+pub fn is_gallery_callback(q: CallbackQuery) -> bool {
+    q.data.as_deref().is_some_and(|d| d.starts_with("gal:"))
+}
+
+/// دستور /images؛ با آرگومان مستقیماً جستجو می‌کند
+pub async fn start_gallery(bot: Bot, dialogue: MyDialogue, msg: Message, args: String) -> HandlerResult {
+    let client = match client(msg.chat.id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    if args.trim().is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "شناسه، نام یا بارکد محصولی که می‌خواهید تصاویرش را ببینید بفرستید.\nبرای انصراف /cancel",
+        )
+        .await?;
+        dialogue.update(State::GalleryFindProduct).await?;
+        return Ok(());
+    }
+
+    if show_matches(&bot, &client, msg.chat.id, &args).await? {
+        dialogue.update(State::Start).await?;
+    }
+    Ok(())
+}
+
+/// دریافت عبارت جستجو بعد از /images بدون آرگومان
+pub async fn receive_gallery_query(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+    let Some(text) = msg.text() else {
+        bot.send_message(msg.chat.id, "شناسه، نام یا بارکد محصول را به صورت متن بفرستید.")
+            .await?;
+        return Ok(());
+    };
+
+    if text.trim().eq_ignore_ascii_case("/cancel") {
+        bot.send_message(msg.chat.id, "مدیریت تصاویر کنسل شد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+
+    let client = match client(msg.chat.id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            dialogue.update(State::Start).await?;
+            return Ok(());
+        }
+    };
+    if show_matches(&bot, &client, msg.chat.id, text).await? {
+        // ادامه با دکمه‌ها انجام می‌شود که به وضعیت مکالمه وابسته نیستند
+        dialogue.update(State::Start).await?;
+    }
+    Ok(())
+}
+
+/// همهٔ دکمه‌های `gal:*`
+///
+/// - `gal:v:{id}` نمایش گالری (پیش‌نمایش عکس‌ها و پیام مدیریت)
+/// - `gal:d:{id}:{image}` پیش‌فرض کردن
+/// - `gal:r:{id}:{image}` جایگزینی (منتظر عکس، فایل یا لینک)
+/// - `gal:a:{id}:{image}` ویرایش متن جایگزین
+/// - `gal:l:{id}` بازگشت به فهرست بدون ارسال دوبارهٔ عکس‌ها
+///
+/// حذف تصویر با همان دکمه‌های `del:ia`/`del:iy` جریان /delete انجام می‌شود.
+pub async fn gallery_callback(bot: Bot, dialogue: MyDialogue, q: CallbackQuery) -> HandlerResult {
+    let (Some(data), Some(message)) = (q.data.as_deref(), q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat.id;
+    let message_id = message.id;
+
+    let parts: Vec<&str> = data.split(':').skip(1).collect();
+    let id = |i: usize| parts.get(i).and_then(|x| x.parse::<u64>().ok());
+
+    // جایگزینی و ویرایش alt منتظر پیام بعدی کاربر می‌مانند
+    if matches!(parts.first().copied(), Some("r" | "a")) && !ensure_idle(&bot, &dialogue, &q).await? {
+        return Ok(());
+    }
+
+    let client = match client(chat_id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.send_message(chat_id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    match (parts.first().copied(), id(1), id(2)) {
+        (Some("v"), Some(product_id), _) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.edit_message_reply_markup(chat_id, message_id).await?;
+            show_gallery(&bot, &client, chat_id, product_id).await?;
+        }
+        (Some("l"), Some(product_id), _) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            refresh_control(&bot, &client, chat_id, message_id, product_id, None).await?;
+        }
+        (Some("d"), Some(product_id), Some(image_id)) => {
+            let result = client
+                .update_product_image(product_id, image_id, &json!({ "default": true }))
+                .await;
+            match result {
+                Ok(()) => {
+                    bot.answer_callback_query(q.id.clone())
+                        .text("⭐️ تصویر پیش‌فرض شد.")
+                        .await?;
+                    refresh_control(&bot, &client, chat_id, message_id, product_id, None).await?;
+                }
+                Err(e) => {
+                    bot.answer_callback_query(q.id.clone()).await?;
+                    report(&bot, chat_id, "setting default product image", e).await?;
+                }
+            }
+        }
+        (Some("r"), Some(product_id), Some(image_id)) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.send_message(
+                chat_id,
+                format!(
+                    "عکس، فایل یا لینک جایگزین تصویر {} را بفرستید.\n\
+                     متن جایگزین و وضعیت پیش‌فرض تصویر قبلی حفظ می‌شود.\nبرای انصراف /cancel",
+                    image_id
+                ),
+            )
+            .await?;
+            dialogue
+                .update(State::GalleryReplaceImage { product_id, image_id })
+                .await?;
+        }
+        (Some("a"), Some(product_id), Some(image_id)) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.send_message(
+                chat_id,
+                format!(
+                    "متن جایگزین (alt) جدید تصویر {} را بفرستید؛ برای خالی کردن «-» بفرستید.\nبرای انصراف /cancel",
+                    image_id
+                ),
+            )
+            .await?;
+            dialogue
+                .update(State::GalleryEditAlt { product_id, image_id })
+                .await?;
+        }
+        _ => {
+            bot.answer_callback_query(q.id.clone()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// دریافت عکس، فایل یا لینک جایگزین؛ تصویر جدید با همان alt و وضعیت پیش‌فرض ثبت و قبلی حذف می‌شود
+pub async fn receive_replacement(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    payload: (u64, u64),
+) -> HandlerResult {
+    let (product_id, image_id) = payload;
+    let chat_id = msg.chat.id;
+
+    if msg.text().is_some_and(|t| t.trim().eq_ignore_ascii_case("/cancel")) {
+        bot.send_message(chat_id, "جایگزینی تصویر کنسل شد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+
+    let client = match client(chat_id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(chat_id, e.user_message()).await?;
+            dialogue.update(State::Start).await?;
+            return Ok(());
+        }
+    };
+    let old = match client.list_product_images(product_id).await {
+        Ok(images) => images.into_iter().find(|img| img.id == image_id),
+        Err(e) => return report(&bot, chat_id, "listing product images", e).await,
+    };
+    let Some(old) = old else {
+        bot.send_message(chat_id, "این تصویر دیگر وجود ندارد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    };
+    let alt = old.image_alt.clone().unwrap_or_default();
+
+    let file = msg
+        .photo()
+        .and_then(|p| p.last())
+        .map(|p| (p.file.id.clone(), None))
+        .or_else(|| msg.document().map(|d| (d.file.id.clone(), d.file_name.clone())));
+    let url = msg
+        .text()
+        .and_then(|t| parse_image_text(t).urls.into_iter().next());
+
+    let uploaded = if let Some((file_id, file_name)) = file {
+        upload_image(&bot, chat_id, product_id, &file_id, file_name.as_deref(), &alt, old.default).await?
+    } else if let Some(url) = url {
+        let mut payload = ProductImageCreate::from_url(product_id, url);
+        payload.image_alt = Some(alt);
+        payload.default = Some(old.default);
+        client.create_product_image(payload).await.map_err(|e| {
+            eprintln!("error in adding product image by url: {}", e);
+            format!("❌ ثبت تصویر از لینک ناموفق بود:\n{}", e.user_message())
+        })
+    } else {
+        bot.send_message(chat_id, "لطفاً عکس، فایل یا لینک تصویر بفرستید یا /cancel را بزنید.")
+            .await?;
+        return Ok(());
+    };
+
+    let new_id = match uploaded {
+        Ok(id) => id,
+        Err(problem) => {
+            // در همین مرحله می‌مانیم تا کاربر بتواند دوباره بفرستد
+            bot.send_message(chat_id, problem).await?;
+            return Ok(());
+        }
+    };
+
+    let note = match client.delete_product_image(product_id, image_id).await {
+        Ok(()) => format!("🔁 تصویر {} با تصویر {} جایگزین شد.", image_id, new_id),
+        Err(e) => {
+            eprintln!("error in deleting replaced image {}: {}", image_id, e);
+            format!(
+                "⚠️ تصویر جدید ({}) ثبت شد ولی حذف تصویر قبلی ناموفق بود:\n{}",
+                new_id,
+                e.user_message()
+            )
+        }
+    };
+    send_control(&bot, &client, chat_id, product_id, Some(note)).await?;
+    dialogue.update(State::Start).await?;
+    Ok(())
+}
+
+/// دریافت متن جایگزین جدید
+pub async fn receive_alt(bot: Bot, dialogue: MyDialogue, msg: Message, payload: (u64, u64)) -> HandlerResult {
+    let (product_id, image_id) = payload;
+    let chat_id = msg.chat.id;
+
+    let Some(text) = msg.text().map(str::trim) else {
+        bot.send_message(chat_id, "متن جایگزین را به صورت متن بفرستید.").await?;
+        return Ok(());
+    };
+
+    if text.eq_ignore_ascii_case("/cancel") {
+        bot.send_message(chat_id, "ویرایش متن جایگزین کنسل شد.").await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+
+    let alt = if text == "-" { "" } else { text };
+    let client = match client(chat_id) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(chat_id, e.user_message()).await?;
+            dialogue.update(State::Start).await?;
+            return Ok(());
+        }
+    };
+    if let Err(e) = client
+        .update_product_image(product_id, image_id, &json!({ "image_alt": alt }))
+        .await
+    {
+        return report(&bot, chat_id, "updating product image alt", e).await;
+    }
+
+    let note = format!("✏️ متن جایگزین تصویر {} به‌روز شد.", image_id);
+    send_control(&bot, &client, chat_id, product_id, Some(note)).await?;
+    dialogue.update(State::Start).await?;
+    Ok(())
+}
+
+/// پیش‌نمایش همهٔ تصاویر (به صورت آلبوم با شمارهٔ هر عکس) و سپس پیام مدیریت
+async fn show_gallery(bot: &Bot, client: &TorobClient, chat_id: ChatId, product_id: u64) -> HandlerResult {
+    let images = match client.list_product_images(product_id).await {
+        Ok(images) => images,
+        Err(e) => return report(bot, chat_id, "listing product images", e).await,
+    };
+
+    // آدرس تصویر ممکن است برای تلگرام قابل دسترس نباشد؛ بایت‌ها را خودمان می‌خوانیم
+    let mut media: Vec<InputMedia> = Vec::new();
+    for (n, img) in images.iter().enumerate() {
+        let Some(url) = img.image.as_deref() else {
+            continue;
+        };
+        let bytes = match client.fetch_image_bytes(url).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("cannot fetch image {} of product {}: {}", img.id, product_id, e);
+                continue;
+            }
+        };
+        // تلگرام نوع فایل را از پسوند نام می‌خواند؛ پسوند از خود بایت‌ها تعیین می‌شود
+        let Some(format) = ImageFormat::sniff(&bytes) else {
+            eprintln!("image {} of product {} is not a known image format", img.id, product_id);
+            continue;
+        };
+        let file = InputFile::memory(bytes).file_name(format!("{}.{}", img.id, format.extension()));
+        let caption = format!("{}{}", n + 1, if img.default { " ⭐️" } else { "" });
+        media.push(InputMedia::Photo(InputMediaPhoto::new(file).caption(caption)));
+    }
+
+    for chunk in media.chunks(GALLERY_CHUNK) {
+        // پیام مدیریت حتی بدون پیش‌نمایش قابل استفاده است
+        if let Err(e) = send_media(bot, chat_id, chunk.to_vec()).await {
+            eprintln!("cannot send gallery of product {}: {}", product_id, e);
+        }
+    }
+
+    let note = (media.len() < images.len()).then(|| {
+        format!("⚠️ پیش‌نمایش {} تصویر در دسترس نبود.", images.len() - media.len())
+    });
+    let (text, keyboard) = gallery_control(product_id, &images, note);
+    bot.send_message(chat_id, text).reply_markup(keyboard).await?;
+    Ok(())
+}
+
+/// فرستادن پیام مدیریت تازه (بعد از پیام متنی کاربر)
+async fn send_control(
+    bot: &Bot,
+    client: &TorobClient,
+    chat_id: ChatId,
+    product_id: u64,
+    note: Option<String>,
+) -> HandlerResult {
+    match client.list_product_images(product_id).await {
+        Ok(images) => {
+            let (text, keyboard) = gallery_control(product_id, &images, note);
+            bot.send_message(chat_id, text).reply_markup(keyboard).await?;
+            Ok(())
+        }
+        Err(e) => report(bot, chat_id, "listing product images", e).await,
+    }
+}
+
+/// ویرایش همان پیام مدیریت با فهرست به‌روز
+async fn refresh_control(
+    bot: &Bot,
+    client: &TorobClient,
+    chat_id: ChatId,
+    message_id: MessageId,
+    product_id: u64,
+    note: Option<String>,
+) -> HandlerResult {
+    match client.list_product_images(product_id).await {
+        Ok(images) => {
+            let (text, keyboard) = gallery_control(product_id, &images, note);
+            bot.edit_message_text(chat_id, message_id, text)
+                .reply_markup(keyboard)
+                .await?;
+            Ok(())
+        }
+        Err(e) => report(bot, chat_id, "listing product images", e).await,
+    }
+}
+
+/// متن و دکمه‌های مدیریت گالری؛ شماره‌ها همان شماره‌های زیر عکس‌های پیش‌نمایش است
+fn gallery_control(
+    product_id: u64,
+    images: &[ProductImage],
+    note: Option<String>,
+) -> (String, InlineKeyboardMarkup) {
+    let mut lines: Vec<String> = note.into_iter().collect();
+    if images.is_empty() {
+        lines.push(format!("محصول {} تصویری ندارد.", product_id));
+    } else {
+        lines.push(format!("🖼 تصاویر محصول {}:", product_id));
+        for (n, img) in images.iter().enumerate() {
+            let alt = img.image_alt.as_deref().filter(|a| !a.is_empty()).unwrap_or("بدون متن جایگزین");
+            let star = if img.default { " ⭐️ پیش‌فرض" } else { "" };
+            lines.push(format!("{}. (id: {}) {}{}", n + 1, img.id, alt, star));
+        }
+    }
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = images
+        .iter()
+        .enumerate()
+        .map(|(n, img)| {
+            let data = |action: &str| format!("gal:{}:{}:{}", action, product_id, img.id);
+            let n = n + 1;
+            let mut row = Vec::new();
+            if !img.default {
+                row.push(InlineKeyboardButton::callback(format!("⭐️ {}", n), data("d")));
+            }
+            row.push(InlineKeyboardButton::callback(format!("🔁 {}", n), data("r")));
+            row.push(InlineKeyboardButton::callback(format!("✏️ {}", n), data("a")));
+            row.push(InlineKeyboardButton::callback(
+                format!("🗑 {}", n),
+                format!("del:ia:{}:{}", product_id, img.id),
+            ));
+            row
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback(
+        "🔄 نمایش دوباره",
+        format!("gal:v:{}", product_id),
+    )]);
+
+    (lines.join("\n"), InlineKeyboardMarkup::new(rows))
+}
+
+/// جستجو؛ یک نتیجه مستقیماً گالری را باز می‌کند. برمی‌گرداند: آیا چیزی پیدا شد
+async fn show_matches(
+    bot: &Bot,
+    client: &TorobClient,
+    chat_id: ChatId,
+    query: &str,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    pick_product(bot, client, chat_id, query, "gal:v", None, |product| async move {
+        show_gallery(bot, client, chat_id, product.id).await
+    })
+    .await
+}
+
+#[cfg(test)]
+mod test_gallery_endpoints {
+    use super::*;
+
+    fn image(id: u64, alt: Option<&str>, default: bool) -> ProductImage {
+        ProductImage {
+            id,
+            image: Some(format!("/media/{}.jpg", id)),
+            image_alt: alt.map(str::to_string),
+            default,
+        }
+    }
+
+    #[test]
+    fn test_gallery_control_rows() {
+        let images = vec![image(5, Some("جلو"), true), image(9, None, false)];
+        let (text, keyboard) = gallery_control(3, &images, None);
+        assert!(text.contains("1. (id: 5) جلو ⭐️ پیش‌فرض"));
+        assert!(text.contains("2. (id: 9) بدون متن جایگزین"));
+
+        let rows = &keyboard.inline_keyboard;
+        // تصویر پیش‌فرض دکمهٔ «پیش‌فرض کن» ندارد
+        assert_eq!(rows[0].len(), 3);
+        assert_eq!(rows[1].len(), 4);
+        assert!(matches!(
+            &rows[1][0].kind,
+            teloxide::types::InlineKeyboardButtonKind::CallbackData(d) if d == "gal:d:3:9"
+        ));
+        assert!(matches!(
+            &rows[1][3].kind,
+            teloxide::types::InlineKeyboardButtonKind::CallbackData(d) if d == "del:ia:3:9"
+        ));
+        assert_eq!(rows.len(), 3);
+
+        let (text, keyboard) = gallery_control(3, &[], Some("🗑 تصویر 5 حذف شد.".into()));
+        assert!(text.starts_with("🗑"));
+        assert_eq!(keyboard.inline_keyboard.len(), 1);
+    }
+}
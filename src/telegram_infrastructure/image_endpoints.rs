@@ -0,0 +1,405 @@
+use crate::services::image_processing::process_image;
+use crate::services::models::product_image::ProductImageCreate;
+use crate::services::product_image_service::submit_product_image;
+use crate::telegram_infrastructure::endpoints::{HandlerResult, MyDialogue};
+use crate::telegram_infrastructure::models::image_batch::{ImageBatch, ImageSource, UploadedImage};
+use crate::telegram_infrastructure::models::product_draft::ProductDraft;
+use crate::telegram_infrastructure::models::state::State;
+use crate::telegram_infrastructure::product_wizard::draft_summary;
+use crate::utilities::config::config;
+use crate::utilities::image_format::ImageFormat;
+use teloxide::Bot;
+use teloxide::net::Download;
+use teloxide::payloads::{EditMessageTextSetters, SendDocumentSetters, SendMessageSetters, SendPhotoSetters};
+use teloxide::prelude::{CallbackQuery, ChatId, Message};
+use teloxide::requests::Requester;
+use teloxide::types::{
+    InlineKeyboardButton, InlineKeyboardMarkup, InputFile, InputMedia, InputMediaDocument, InputMediaPhoto, MessageId,
+};
+
+/// حداکثر عکس در هر media group تلگرام
+pub const GALLERY_CHUNK: usize = 10;
+
+/// نشانه‌هایی در کپشن یا متن که تصویر را پیش‌فرض می‌کنند
+const DEFAULT_MARKS: &[&str] = &["⭐️", "⭐", "/default", "پیش‌فرض"];
+
+/// ====== دریافت تصاویر محصول ساخته‌شده ======
+///
+/// هر عکس (تکی یا بخشی از آلبوم)، فایل تصویر یا لینک تصویر جداگانه ثبت می‌شود و در همین
+/// وضعیت می‌مانیم تا کاربر /done یا دکمهٔ «پایان» را بزند. عکس‌های یک آلبوم پیام‌های جدا هستند
+/// ولی پیشرفتشان در یک پیام ویرایش می‌شود.
+/// کپشن (یا متن کنار لینک) alt تصویر است و «⭐️» آن را پیش‌فرض می‌کند؛ بدون کپشن alt همان نام محصول است.
+pub async fn receive_product_image(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    payload: (u64, ProductDraft, ImageBatch),
+) -> HandlerResult {
+    let (product_id, draft, mut batch) = payload;
+    let chat_id = msg.chat.id;
+
+    if let Some(text) = msg.text() {
+        let text = text.trim();
+        if text.eq_ignore_ascii_case("/done") || text.eq_ignore_ascii_case("/cancel") {
+            return finish(&bot, dialogue, chat_id, product_id, &draft, &batch).await;
+        }
+    }
+
+    let options = parse_image_text(msg.caption().or(msg.text()).unwrap_or_default());
+    let alt = options.alt.clone().unwrap_or_else(|| draft.product.name.clone());
+
+    // عکس فشرده‌شدهٔ تلگرام، تصویری که «به صورت فایل» (بدون فشرده‌سازی) آمده، یا لینک
+    let mut outcomes: Vec<(ImageSource, Result<u64, String>)> = Vec::new();
+    if let Some(photo) = msg.photo().and_then(|p| p.last()) {
+        let file_id = photo.file.id.clone();
+        let result = upload_image(&bot, chat_id, product_id, &file_id, None, &alt, options.default).await?;
+        outcomes.push((ImageSource::Photo(file_id), result));
+    } else if let Some(doc) = msg.document() {
+        let file_id = doc.file.id.clone();
+        let file_name = doc.file_name.as_deref();
+        let result = upload_image(&bot, chat_id, product_id, &file_id, file_name, &alt, options.default).await?;
+        outcomes.push((ImageSource::Document(file_id), result));
+    } else if msg.text().is_some() && !options.urls.is_empty() {
+        for (i, url) in options.urls.iter().enumerate() {
+            let mut payload = ProductImageCreate::from_url(product_id, url.clone());
+            payload.image_alt = Some(alt.clone());
+            // با چند لینک در یک پیام فقط اولی پیش‌فرض می‌شود
+            payload.default = Some(options.default && i == 0);
+            let result = submit_product_image(chat_id.0.to_string(), payload)
+                .await
+                .map_err(|e| {
+                    eprintln!("error in adding product image by url: {}", e);
+                    format!("❌ ثبت تصویر از لینک ناموفق بود:\n{}\n{}", url, e.user_message())
+                });
+            outcomes.push((ImageSource::Url(url.clone()), result));
+        }
+    } else {
+        bot.send_message(
+            chat_id,
+            "لطفاً تصویر (عکس، فایل یا لینک) ارسال کنید یا برای پایان /done را بزنید.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    for (i, (source, result)) in outcomes.into_iter().enumerate() {
+        match result {
+            Ok(image_id) => {
+                let default = options.default && i == 0;
+                if default {
+                    batch.uploaded.iter_mut().for_each(|img| img.default = false);
+                }
+                batch.uploaded.push(UploadedImage {
+                    image_id,
+                    source,
+                    default,
+                });
+            }
+            Err(problem) => {
+                batch.failed += 1;
+                bot.send_message(chat_id, problem).await?;
+            }
+        }
+    }
+
+    // عکس‌های بعدی همان آلبوم پیام پیشرفت قبلی را ویرایش می‌کنند
+    let text = progress_text(&batch);
+    let group = msg.media_group_id().map(str::to_string);
+    match (&batch.progress, &group) {
+        (Some((last, message_id)), Some(group)) if last == group => {
+            bot.edit_message_text(chat_id, MessageId(*message_id), text)
+                .reply_markup(done_keyboard())
+                .await?;
+        }
+        _ => {
+            let sent = bot
+                .send_message(chat_id, text)
+                .reply_markup(done_keyboard())
+                .await?;
+            batch.progress = group.map(|g| (g, sent.id.0));
+        }
+    }
+
+    dialogue
+        .update(State::ReceiveProductImage {
+            product_id,
+            draft,
+            batch,
+        })
+        .await?;
+    Ok(())
+}
+
+/// دکمهٔ «پایان» زیر پیام‌های پیشرفت
+pub async fn image_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    payload: (u64, ProductDraft, ImageBatch),
+) -> HandlerResult {
+    let (product_id, draft, batch) = payload;
+    bot.answer_callback_query(q.id.clone()).await?;
+    if q.data.as_deref() != Some("img:done") {
+        return Ok(());
+    }
+    if let Some(m) = q.message.as_ref() {
+        bot.edit_message_reply_markup(m.chat.id, m.id).await?;
+    }
+    let chat_id = dialogue.chat_id();
+    finish(&bot, dialogue, chat_id, product_id, &draft, &batch).await
+}
+
+/// آنچه از کپشن یا متن یک پیام تصویر برداشت می‌شود
+#[derive(Debug, Default, PartialEq)]
+pub struct ImageOptions {
+    /// لینک‌های http(s) داخل متن
+    pub urls: Vec<String>,
+    /// بقیهٔ متن (بدون لینک و نشانهٔ پیش‌فرض)
+    pub alt: Option<String>,
+    pub default: bool,
+}
+
+pub fn parse_image_text(text: &str) -> ImageOptions {
+    let mut options = ImageOptions::default();
+    let mut rest: Vec<&str> = Vec::new();
+    for word in text.split_whitespace() {
+        let is_url = (word.starts_with("https://") || word.starts_with("http://"))
+            && reqwest::Url::parse(word).is_ok();
+        if is_url {
+            options.urls.push(word.to_string());
+        } else if DEFAULT_MARKS.contains(&word) {
+            options.default = true;
+        } else {
+            rest.push(word);
+        }
+    }
+    options.alt = Some(rest.join(" ")).filter(|alt| !alt.is_empty());
+    options
+}
+
+/// بررسی، دانلود از تلگرام، کوچک‌سازی و آپلود یک تصویر؛
+/// خطای بیرونی فقط خطای تلگرام است و خطای داخلی پیامی برای کاربر
+pub async fn upload_image(
+    bot: &Bot,
+    chat_id: ChatId,
+    product_id: u64,
+    file_id: &str,
+    file_name: Option<&str>,
+    alt: &str,
+    default: bool,
+) -> Result<Result<u64, String>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let file = bot.get_file(file_id).await?;
+    let image_config = &config().images;
+
+    if u64::from(file.size) > image_config.max_input_bytes {
+        return Ok(Err(format!(
+            "❌ حجم فایل باید کمتر از {} کیلوبایت باشد.",
+            image_config.max_input_bytes / 1024
+        )));
+    }
+
+    // دانلود بایت‌ها از تلگرام
+    let mut bytes: Vec<u8> = Vec::new();
+    bot.download_file(&file.path, &mut bytes).await?;
+
+    // قالب واقعی از امضای فایل؛ پسوند مسیر یا نام فایل ملاک نیست
+    let allowed_exts = &image_config.allowed_extensions;
+    let Some(format) = ImageFormat::sniff(&bytes) else {
+        return Ok(Err("❌ این فایل تصویر شناخته‌شده‌ای نیست.".into()));
+    };
+    if !format.is_allowed(allowed_exts) {
+        return Ok(Err(format!(
+            "❌ فرمت فایل پشتیبانی نمی‌شود. فرمت‌های مجاز: {:?}",
+            allowed_exts
+        )));
+    }
+
+    // کوچک‌سازی و رمزگذاری دوباره (کار سنگین پردازنده، خارج از رشته‌های async)
+    let processed = match tokio::task::spawn_blocking(move || process_image(&bytes, &config().images)).await? {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("error in processing product image: {}", e);
+            return Ok(Err(e.user_message()));
+        }
+    };
+
+    // نام فایل کاربر (یا انتهای مسیر تلگرام) با پسوند قالب خروجی
+    let stem = file_name
+        .or_else(|| file.path.rsplit('/').next())
+        .map(|n| n.rsplit_once('.').map_or(n, |(stem, _)| stem).trim())
+        .filter(|n| !n.is_empty())
+        .unwrap_or("image");
+    let filename = format!("{}.{}", stem, processed.extension);
+
+    // آپلود به بک‌اند
+    let mut payload = ProductImageCreate::from_file(product_id, filename, processed.bytes);
+    payload.image_alt = Some(alt.to_string());
+    payload.default = Some(default);
+    match submit_product_image(chat_id.0.to_string(), payload).await {
+        Ok(image_id) => Ok(Ok(image_id)),
+        Err(e) => {
+            eprintln!("error in uploading product image: {}", e);
+            Ok(Err(format!(
+                "❌ خطا در آپلود تصویر:\n{}\nمی‌توانید دوباره بفرستید.",
+                e.user_message()
+            )))
+        }
+    }
+}
+
+fn progress_text(batch: &ImageBatch) -> String {
+    let mut text = format!("📤 {} تصویر آپلود شد.", batch.uploaded.len());
+    if let Some(n) = default_position(batch) {
+        text.push_str(&format!("\n⭐️ تصویر پیش‌فرض: شمارهٔ {}", n));
+    }
+    if batch.failed > 0 {
+        text.push_str(&format!("\n❌ {} تصویر ناموفق.", batch.failed));
+    }
+    text.push_str(
+        "\nتصویر یا لینک بعدی را بفرستید یا «پایان» را بزنید.\n\
+         (کپشن = متن جایگزین؛ «⭐️» در کپشن = تصویر پیش‌فرض)",
+    );
+    text
+}
+
+/// شمارهٔ تصویر پیش‌فرض (از ۱)
+fn default_position(batch: &ImageBatch) -> Option<usize> {
+    batch.uploaded.iter().position(|img| img.default).map(|i| i + 1)
+}
+
+fn done_keyboard() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        "✅ پایان",
+        "img:done",
+    )]])
+}
+
+/// پیام نهایی: پیش‌نمایش گالری (خلاصه به عنوان کپشن اولین عکس) و بازگشت به شروع
+async fn finish(
+    bot: &Bot,
+    dialogue: MyDialogue,
+    chat_id: ChatId,
+    product_id: u64,
+    draft: &ProductDraft,
+    batch: &ImageBatch,
+) -> HandlerResult {
+    let default = default_position(batch)
+        .map(|n| format!(" (پیش‌فرض: شمارهٔ {})", n))
+        .unwrap_or_default();
+    let summary = format!(
+        "✅ محصول با موفقیت ایجاد شد.\n\
+         ─────────────────────\n\
+         {}\n\
+         🆔 شناسه محصول: {}\n\
+         🖼 تعداد تصاویر: {}{}\
+         \n برای ثبت محصول بعدی روی /start  کلیک کنید",
+        draft_summary(draft),
+        product_id,
+        batch.uploaded.len(),
+        default
+    );
+
+    // عکس‌ها و فایل‌ها در یک media group مخلوط نمی‌شوند
+    let (documents, photos): (Vec<&UploadedImage>, Vec<&UploadedImage>) = batch
+        .uploaded
+        .iter()
+        .partition(|img| matches!(img.source, ImageSource::Document(_)));
+    let mut caption = Some(summary);
+    for chunk in photos.chunks(GALLERY_CHUNK).chain(documents.chunks(GALLERY_CHUNK)) {
+        // تصاویر ثبت شده‌اند؛ خطای پیش‌نمایش (مثلاً لینکی که تلگرام باز نمی‌کند) جریان را متوقف نمی‌کند
+        match send_gallery_chunk(bot, chat_id, chunk, caption.clone()).await {
+            Ok(()) => caption = None,
+            Err(e) => eprintln!("cannot send gallery preview: {}", e),
+        }
+    }
+    if let Some(summary) = caption {
+        bot.send_message(chat_id, summary).await?;
+    }
+
+    // پایان جریان
+    dialogue.update(State::Start).await?;
+    Ok(())
+}
+
+fn input_file(source: &ImageSource) -> InputFile {
+    match source {
+        ImageSource::Photo(id) | ImageSource::Document(id) => InputFile::file_id(id.clone()),
+        // لینک هنگام دریافت بررسی شده؛ اگر باز هم خراب باشد فقط پیش‌نمایش ناموفق می‌شود
+        ImageSource::Url(url) => match reqwest::Url::parse(url) {
+            Ok(url) => InputFile::url(url),
+            Err(_) => InputFile::file_id(url.clone()),
+        },
+    }
+}
+
+/// ارسال حداکثر ده تصویر هم‌نوع؛ کپشن روی اولین تصویر می‌نشیند
+async fn send_gallery_chunk(
+    bot: &Bot,
+    chat_id: ChatId,
+    chunk: &[&UploadedImage],
+    caption: Option<String>,
+) -> HandlerResult {
+    let mut caption = caption;
+    let media: Vec<InputMedia> = chunk
+        .iter()
+        .map(|image| {
+            let file = input_file(&image.source);
+            if matches!(image.source, ImageSource::Document(_)) {
+                let mut document = InputMediaDocument::new(file);
+                if let Some(c) = caption.take() {
+                    document = document.caption(c);
+                }
+                InputMedia::Document(document)
+            } else {
+                let mut photo = InputMediaPhoto::new(file);
+                if let Some(c) = caption.take() {
+                    photo = photo.caption(c);
+                }
+                InputMedia::Photo(photo)
+            }
+        })
+        .collect();
+    send_media(bot, chat_id, media).await
+}
+
+/// ارسال حداکثر `GALLERY_CHUNK` عکس یا فایل؛ media group حداقل دو عضو می‌خواهد،
+/// پس یک عضو تنها با `send_photo`/`send_document` (با همان کپشن) فرستاده می‌شود
+pub async fn send_media(bot: &Bot, chat_id: ChatId, media: Vec<InputMedia>) -> HandlerResult {
+    match media.as_slice() {
+        [] => {}
+        [InputMedia::Photo(photo)] => {
+            let mut req = bot.send_photo(chat_id, photo.media.clone());
+            if let Some(c) = photo.caption.clone() {
+                req = req.caption(c);
+            }
+            req.await?;
+        }
+        [InputMedia::Document(document)] => {
+            let mut req = bot.send_document(chat_id, document.media.clone());
+            if let Some(c) = document.caption.clone() {
+                req = req.caption(c);
+            }
+            req.await?;
+        }
+        _ => {
+            bot.send_media_group(chat_id, media).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_image_endpoints {
+    use super::*;
+
+    #[test]
+    fn test_parse_image_text() {
+        let options = parse_image_text("https://shop.ir/a.jpg  ⭐️ کفش قرمز\nhttp://cdn.ir/b.png");
+        assert_eq!(options.urls, vec!["https://shop.ir/a.jpg", "http://cdn.ir/b.png"]);
+        assert_eq!(options.alt.as_deref(), Some("کفش قرمز"));
+        assert!(options.default);
+
+        assert_eq!(parse_image_text("  "), ImageOptions::default());
+        assert!(parse_image_text("https:// نمای جلو").urls.is_empty());
+    }
+}
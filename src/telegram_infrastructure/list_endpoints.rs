@@ -0,0 +1,313 @@
+use crate::services::models::product::{ProductPage, ProductQuery};
+use crate::services::torob_client::TorobClient;
+use crate::telegram_infrastructure::endpoints::HandlerResult;
+use crate::utilities::database::database;
+use crate::utilities::text::parse_number;
+use rusqlite::{OptionalExtension, params};
+use teloxide::Bot;
+use teloxide::payloads::{AnswerCallbackQuerySetters, EditMessageTextSetters, SendMessageSetters};
+use teloxide::prelude::{CallbackQuery, ChatId, Message};
+use teloxide::requests::Requester;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+/// تعداد فیلترهای نگه‌داشته‌شده برای هر چت؛ دکمه‌های لیست‌های قدیمی‌تر غیرفعال می‌شوند
+const MAX_FILTERS_PER_CHAT: i64 = 50;
+
+/// ذخیرهٔ فیلتر در جدول `product_filters`؛ شناسهٔ ردیف در callback دکمه‌ها می‌رود
+///
+/// جستجوی متنی ممکن است از سقف ۶۴ بایتی callback تلگرام بلندتر باشد، برای همین خود فیلتر در callback نیست.
+fn save_filter(chat_id: ChatId, query: &ProductQuery) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+    let json = serde_json::to_string(query)?;
+    let conn = database().lock().map_err(|_| "database lock poisoned")?;
+    conn.execute(
+        "INSERT INTO product_filters (chat_id, query) VALUES (?1, ?2)",
+        params![chat_id.0, json],
+    )?;
+    let key = conn.last_insert_rowid();
+    // شناسه‌ها بین همهٔ چت‌ها مشترک است؛ سقف با شمارش ردیف‌های همین چت اعمال می‌شود
+    conn.execute(
+        "DELETE FROM product_filters WHERE chat_id = ?1 AND id NOT IN
+             (SELECT id FROM product_filters WHERE chat_id = ?1 ORDER BY id DESC LIMIT ?2)",
+        params![chat_id.0, MAX_FILTERS_PER_CHAT],
+    )?;
+    Ok(key)
+}
+
+/// فیلتر ذخیره‌شده با شناسهٔ `key`؛ فقط اگر متعلق به همین چت باشد
+fn saved_filter(chat_id: ChatId, key: i64) -> Option<ProductQuery> {
+    let conn = database().lock().ok()?;
+    let json: String = conn
+        .query_row(
+            "SELECT query FROM product_filters WHERE id = ?1 AND chat_id = ?2",
+            params![key, chat_id.0],
+            |row| row.get(0),
+        )
+        .optional()
+        .ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+/// آیا این callback مربوط به لیست محصولات است (مستقل از وضعیت مکالمه)
+pub fn is_listing_callback(q: CallbackQuery) -> bool {
+    q.data.as_deref().is_some_and(|d| d.starts_with("list:"))
+}
+
+/// خواندن فیلتر از آرگومان‌های /products
+///
+/// مثال: `/products cat:12 فعال کفش` ← دستهٔ 12، فقط فعال‌ها، جستجوی «کفش»
+pub fn parse_filter(args: &str) -> ProductQuery {
+    let mut query = ProductQuery {
+        page: 1,
+        ..ProductQuery::default()
+    };
+    let mut words: Vec<&str> = Vec::new();
+
+    for word in args.split_whitespace() {
+        let lower = word.to_lowercase();
+        if let Some(id) = lower
+            .strip_prefix("cat:")
+            .or_else(|| lower.strip_prefix("دسته:"))
+            .and_then(parse_number::<u64>)
+        {
+            query.category = Some(id);
+            continue;
+        }
+        match lower.as_str() {
+            "فعال" | "available" | "available:yes" => query.available = Some(true),
+            "غیرفعال" | "unavailable" | "available:no" => query.available = Some(false),
+            _ => words.push(word),
+        }
+    }
+
+    if !words.is_empty() {
+        query.search = Some(words.join(" "));
+    }
+    query
+}
+
+/// دستور /products
+pub async fn start_listing(bot: Bot, msg: Message, args: String) -> HandlerResult {
+    let client = match TorobClient::for_chat(&msg.chat.id.0.to_string()) {
+        Ok(c) => c,
+        Err(e) => {
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+            return Ok(());
+        }
+    };
+
+    let query = parse_filter(&args);
+    let key = match save_filter(msg.chat.id, &query) {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("error in saving product filter: {}", e);
+            bot.send_message(msg.chat.id, "❌ خطای داخلی؛ دوباره تلاش کنید.").await?;
+            return Ok(());
+        }
+    };
+
+    match client.list_products(&query).await {
+        Ok(page) => {
+            bot.send_message(msg.chat.id, listing_text(&query, &page))
+                .reply_markup(listing_keyboard(key, &query, &page))
+                .await?;
+        }
+        Err(e) => {
+            eprintln!("error in listing products: {}", e);
+            bot.send_message(msg.chat.id, e.user_message()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// دکمه‌های قبلی/بعدی و فیلتر فعال بودن؛ همان پیام ویرایش می‌شود
+pub async fn listing_callback(bot: Bot, q: CallbackQuery) -> HandlerResult {
+    let (Some(data), Some(message)) = (q.data.as_deref(), q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat.id;
+
+    let Some((mut key, action, value)) = parse_listing_data(data) else {
+        // list:noop (دکمهٔ شمارهٔ صفحه)
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+
+    let Some(mut query) = saved_filter(chat_id, key) else {
+        bot.answer_callback_query(q.id.clone())
+            .text("این لیست قدیمی است؛ دوباره /products را بفرستید.")
+            .await?;
+        return Ok(());
+    };
+
+    match (action, value.parse::<u32>()) {
+        ("p", Ok(page)) => query.page = page,
+        ("a", _) => {
+            query.available = match value {
+                "1" => Some(true),
+                "0" => Some(false),
+                _ => None,
+            };
+            query.page = 1;
+            // فیلتر عوض شده؛ دکمه‌های جدید به ردیف تازه اشاره می‌کنند
+            key = match save_filter(chat_id, &query) {
+                Ok(key) => key,
+                Err(e) => {
+                    eprintln!("error in saving product filter: {}", e);
+                    bot.answer_callback_query(q.id.clone()).await?;
+                    return Ok(());
+                }
+            };
+        }
+        _ => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            return Ok(());
+        }
+    }
+
+    let result = match TorobClient::for_chat(&chat_id.0.to_string()) {
+        Ok(client) => client.list_products(&query).await,
+        Err(e) => Err(e),
+    };
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    match result {
+        Ok(page) => {
+            bot.edit_message_text(chat_id, message.id, listing_text(&query, &page))
+                .reply_markup(listing_keyboard(key, &query, &page))
+                .await?;
+        }
+        Err(e) => {
+            eprintln!("error in listing products: {}", e);
+            bot.send_message(chat_id, e.user_message()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// `list:{key}:p:{page}` یا `list:{key}:a:{1|0|all}` ← (شناسهٔ فیلتر، عمل، مقدار)
+fn parse_listing_data(data: &str) -> Option<(i64, &str, &str)> {
+    let mut parts = data.strip_prefix("list:")?.splitn(3, ':');
+    let key = parts.next()?.parse::<i64>().ok()?;
+    Some((key, parts.next()?, parts.next()?))
+}
+
+/// متن یک صفحه از لیست
+pub fn listing_text(query: &ProductQuery, page: &ProductPage) -> String {
+    let mut header = match page.total_pages {
+        Some(total) => format!("📦 محصولات — صفحهٔ {} از {}", page.page, total),
+        None => format!("📦 محصولات — صفحهٔ {}", page.page),
+    };
+    if let Some(count) = page.count {
+        header.push_str(&format!(" ({} مورد)", count));
+    }
+
+    let mut filters: Vec<String> = Vec::new();
+    if let Some(search) = &query.search {
+        filters.push(format!("جستجو: «{}»", search));
+    }
+    if let Some(category) = query.category {
+        filters.push(format!("دسته‌بندی: {}", category));
+    }
+    match query.available {
+        Some(true) => filters.push("فقط فعال‌ها".into()),
+        Some(false) => filters.push("فقط غیرفعال‌ها".into()),
+        None => {}
+    }
+
+    let mut lines = vec![header];
+    if !filters.is_empty() {
+        lines.push(format!("🔎 {}", filters.join(" • ")));
+    }
+    lines.push("─────────────────────".into());
+
+    if page.items.is_empty() {
+        lines.push("محصولی پیدا نشد.".into());
+    }
+    for p in &page.items {
+        let av = match p.fields.available {
+            Some(false) => "⛔️",
+            _ => "✅",
+        };
+        let price = p
+            .fields
+            .price
+            .map(|v| format!("{} تومان", v))
+            .unwrap_or_else(|| "بدون قیمت".into());
+        lines.push(format!("{} {} — {} (id: {})", av, p.fields.name, price, p.id));
+    }
+    lines.join("\n")
+}
+
+/// دکمه‌های صفحه‌بندی و فیلتر فعال بودن؛ `key` شناسهٔ فیلتر ذخیره‌شده است
+pub fn listing_keyboard(key: i64, query: &ProductQuery, page: &ProductPage) -> InlineKeyboardMarkup {
+    let mut nav: Vec<InlineKeyboardButton> = Vec::new();
+    if page.has_previous && page.page > 1 {
+        nav.push(InlineKeyboardButton::callback(
+            "◀️ قبلی",
+            format!("list:{}:p:{}", key, page.page - 1),
+        ));
+    }
+    nav.push(InlineKeyboardButton::callback(
+        page.page.to_string(),
+        "list:noop",
+    ));
+    if page.has_next {
+        nav.push(InlineKeyboardButton::callback(
+            "بعدی ▶️",
+            format!("list:{}:p:{}", key, page.page + 1),
+        ));
+    }
+
+    let mark = |selected: bool, text: &str| {
+        if selected {
+            format!("• {} •", text)
+        } else {
+            text.to_string()
+        }
+    };
+    let filter_row = vec![
+        InlineKeyboardButton::callback(mark(query.available.is_none(), "همه"), format!("list:{}:a:all", key)),
+        InlineKeyboardButton::callback(mark(query.available == Some(true), "فعال"), format!("list:{}:a:1", key)),
+        InlineKeyboardButton::callback(mark(query.available == Some(false), "غیرفعال"), format!("list:{}:a:0", key)),
+    ];
+
+    InlineKeyboardMarkup::new(vec![nav, filter_row])
+}
+
+#[cfg(test)]
+mod test_list_endpoints {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        let q = parse_filter("cat:۱۲ فعال کفش ورزشی");
+        assert_eq!(q.category, Some(12));
+        assert_eq!(q.available, Some(true));
+        assert_eq!(q.search.as_deref(), Some("کفش ورزشی"));
+        assert_eq!(q.page, 1);
+
+        assert_eq!(parse_filter("  "), ProductQuery { page: 1, ..ProductQuery::default() });
+
+        // دستور بدون آرگومان هم معتبر است
+        use crate::telegram_infrastructure::models::command::Command;
+        use teloxide::utils::command::BotCommands;
+        assert!(matches!(
+            Command::parse("/products", "bot"),
+            Ok(Command::Products(args)) if args.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_saved_filter_roundtrip() {
+        let query = parse_filter("cat:12 کفش ورزشی مردانهٔ مخصوص دویدن در کوهستان");
+        let key = save_filter(ChatId(5), &query).unwrap();
+        assert_eq!(saved_filter(ChatId(5), key), Some(query));
+        // فیلتر چت دیگر خوانده نمی‌شود
+        assert_eq!(saved_filter(ChatId(6), key), None);
+
+        assert_eq!(parse_listing_data(&format!("list:{}:p:3", key)), Some((key, "p", "3")));
+        assert_eq!(parse_listing_data("list:noop"), None);
+        assert_eq!(parse_listing_data("list:p:3"), None);
+    }
+}
@@ -1,3 +1,14 @@
 pub mod models;
 pub mod telegram_bot;
-pub mod endpoints;
\ No newline at end of file
+pub mod endpoints;
+pub mod dialogue_storage;
+pub mod edit_endpoints;
+pub mod list_endpoints;
+pub mod delete_endpoints;
+pub mod wizard;
+pub mod product_wizard;
+pub mod category_picker;
+pub mod product_picker;
+pub mod category_endpoints;
+pub mod image_endpoints;
+pub mod gallery_endpoints;
@@ -0,0 +1,25 @@
+use crate::services::models::category::CategoryCreate;
+use crate::telegram_infrastructure::models::product_draft::ProductDraft;
+use serde::{Deserialize, Serialize};
+
+/// ====== پیش‌نویس دستهٔ جدید ======
+/// اگر از وسط ساخت محصول شروع شده باشد، `resume` مرحله و پیش‌نویس محصول را نگه می‌دارد
+/// تا بعد از ساخت دسته همان‌جا ادامه پیدا کند.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CategoryDraft {
+    pub category: CategoryCreate,
+    pub resume: Option<(String, ProductDraft)>,
+}
+
+impl CategoryDraft {
+    pub fn new(parent: Option<u64>, resume: Option<(String, ProductDraft)>) -> Self {
+        Self {
+            category: CategoryCreate {
+                name: String::new(),
+                parent,
+                available: true,
+            },
+            resume,
+        }
+    }
+}
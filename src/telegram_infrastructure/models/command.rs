@@ -14,4 +14,28 @@ pub enum Command {
     /// حذف اطلاعات اولیه
     #[command(description = "حذف اطلاعات و تغییر توکن")]
     ChangeToken,
-}
\ No newline at end of file
+    /// ویرایش محصول موجود
+    #[command(description = "ویرایش محصول")]
+    Edit,
+    /// لیست و جستجوی محصولات؛ آرگومان اختیاری: `cat:<id>`، «فعال»/«غیرفعال» و متن جستجو
+    #[command(description = "لیست محصولات (مثال: /products cat:12 فعال کفش)")]
+    Products(String),
+    /// حذف محصول یا یکی از تصاویرش (با تأیید)
+    #[command(description = "حذف محصول یا تصویر (مثال: /delete 123)")]
+    Delete(String),
+    /// دیدن و مدیریت تصاویر یک محصول (پیش‌فرض، جایگزینی، متن جایگزین، حذف)
+    #[command(description = "گالری تصاویر محصول (مثال: /images 123)")]
+    Images(String),
+    /// حذف آخرین محصولی که در همین گفتگو ساخته شده
+    #[command(description = "برگرداندن (حذف) آخرین محصول ساخته‌شده")]
+    Undo,
+    /// خواندن دوباره دسته‌بندی‌ها از پنل (کش را دور می‌ریزد)
+    #[command(description = "به‌روزرسانی دسته‌بندی‌ها از پنل")]
+    RefreshCategories,
+    /// ساخت دسته‌بندی جدید در پنل
+    #[command(description = "ساخت دسته‌بندی جدید")]
+    NewCategory,
+    /// مرور دسته‌ها برای تغییر نام و فعال/غیرفعال کردن
+    #[command(description = "مدیریت دسته‌بندی‌ها")]
+    Categories,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// ====== تصاویر آپلودشده در مرحلهٔ تصویر محصول ======
+/// تا کاربر «پایان» را نزند عکس‌ها (تکی یا آلبوم) پشت سر هم اضافه می‌شوند.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImageBatch {
+    /// تصاویر آپلودشده به ترتیب ارسال (برای پیش‌نمایش گالری)
+    pub uploaded: Vec<UploadedImage>,
+    /// تعداد تصاویری که آپلودشان ناموفق بود
+    pub failed: usize,
+    /// پیام پیشرفت آخرین آلبوم: (media_group_id، شناسهٔ پیام) تا بقیهٔ عکس‌های آلبوم همان پیام را ویرایش کنند
+    pub progress: Option<(String, i32)>,
+}
+
+/// یک تصویر ثبت‌شده در پنل
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UploadedImage {
+    /// شناسهٔ تصویر در پنل
+    pub image_id: u64,
+    pub source: ImageSource,
+    /// به عنوان تصویر پیش‌فرض محصول ثبت شده است
+    #[serde(default)]
+    pub default: bool,
+}
+
+/// از کجا آمده؛ برای پیش‌نمایش، فایل‌هایی که «به صورت فایل» آمده‌اند فقط به صورت سند قابل ارسال دوباره‌اند
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ImageSource {
+    Photo(String),
+    Document(String),
+    Url(String),
+}
@@ -1,2 +1,5 @@
 pub mod command;
 pub mod state;
+pub mod product_draft;
+pub mod category_draft;
+pub mod image_batch;
@@ -0,0 +1,27 @@
+use crate::services::models::product::ProductCreate;
+use serde::{Deserialize, Serialize};
+
+/// ====== پیش‌نویس محصول در حال ساخت ======
+/// همهٔ پاسخ‌های wizard در `product` جمع می‌شود؛ بقیه فقط برای نمایش و مسیر مراحل است.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProductDraft {
+    pub product: ProductCreate,
+    /// نام دسته‌بندی انتخاب‌شده (برای خلاصه‌ها)
+    pub category_name: Option<String>,
+    /// آیا کاربر مسیر فیلدهای پیشرفته را انتخاب کرده است
+    pub advanced: bool,
+    /// پیش‌نویس به صفحهٔ بازبینی رسیده؛ ویرایش هر فیلد به همان صفحه برمی‌گردد
+    #[serde(default)]
+    pub reviewing: bool,
+}
+
+impl Default for ProductDraft {
+    fn default() -> Self {
+        Self {
+            product: ProductCreate::new("", 0),
+            category_name: None,
+            advanced: false,
+            reviewing: false,
+        }
+    }
+}
@@ -1,41 +1,85 @@
+use crate::services::models::product::ProductField;
+use crate::telegram_infrastructure::models::category_draft::CategoryDraft;
+use crate::telegram_infrastructure::models::image_batch::ImageBatch;
+use crate::telegram_infrastructure::models::product_draft::ProductDraft;
+use serde::{Deserialize, Serialize};
+
 /// ====== مدل وضعیت مکالمه ======
-#[derive(Clone, Debug)]
+/// قابل سریال‌سازی است تا در SQLite/Redis ذخیره شود و بعد از ری‌استارت ادامه پیدا کند.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub enum State {
     /// استارت اولیه توسط کاربر - نمایش دستورات
+    #[default]
     Start,
 
+    /// وضعیت ذخیره‌شده قابل خواندن نبود؛ در اولین پیام به کاربر خبر می‌دهیم و به `Start` برمی‌گردیم
+    Unreadable,
+
     ReceiveWebSite,
     ReceiveToken,
     // ReceivePassword {
     //     user_name: String,
     // },
 
-    /// منتظر دریافت نام محصول
-    ReceiveProductName,
-
-    /// منتظر دریافت قیمت
-    ReceivePrice {
-        name: String,
+    /// ساخت محصول با wizard: منتظر پاسخ مرحله‌ای با شناسهٔ `step` (تعریف مراحل در `product_wizard`)
+    CreateProduct {
+        step: String,
+        draft: ProductDraft,
     },
 
-    /// منتظر دریافت شناسه دسته‌بندی
-    ReceiveCategoryId {
-        name: String,
-        price: i64,
+    /// بازبینی پیش‌نویس قبل از ارسال به API: ثبت، ویرایش یک فیلد یا انصراف
+    ReviewProduct {
+        draft: ProductDraft,
     },
 
-    /// منتظر دریافت تصویر محصول
+    /// منتظر دریافت تصاویر محصول ساخته‌شده تا زدن «پایان»
     ReceiveProductImage {
-        name: String,
-        price: i64,
-        category_id: u64,
-        category_name: String,
         product_id: u64,
+        draft: ProductDraft,
+        #[serde(default)]
+        batch: ImageBatch,
     },
-}
 
-impl Default for State {
-    fn default() -> Self {
-        State::Start
-    }
-}
\ No newline at end of file
+    /// ویرایش: منتظر شناسه، نام یا بارکد محصول
+    EditFindProduct,
+
+    /// ویرایش: منتظر انتخاب فیلد با دکمه
+    EditChooseField {
+        product_id: u64,
+    },
+
+    /// ویرایش: منتظر مقدار جدید فیلد
+    EditReceiveValue {
+        product_id: u64,
+        field: ProductField,
+    },
+
+    /// حذف: منتظر شناسه، نام یا بارکد محصول
+    DeleteFindProduct,
+
+    /// گالری: منتظر شناسه، نام یا بارکد محصول
+    GalleryFindProduct,
+
+    /// گالری: منتظر عکس، فایل یا لینک جایگزین یک تصویر
+    GalleryReplaceImage {
+        product_id: u64,
+        image_id: u64,
+    },
+
+    /// گالری: منتظر متن جایگزین (alt) جدید یک تصویر
+    GalleryEditAlt {
+        product_id: u64,
+        image_id: u64,
+    },
+
+    /// ساخت دستهٔ جدید با wizard (تعریف مراحل در `category_endpoints`)
+    CreateCategory {
+        step: String,
+        draft: CategoryDraft,
+    },
+
+    /// مدیریت دسته: منتظر نام جدید
+    RenameCategory {
+        category_id: u64,
+    },
+}
@@ -0,0 +1,80 @@
+use crate::services::models::product::Product;
+use crate::services::torob_client::TorobClient;
+use crate::telegram_infrastructure::endpoints::{report, HandlerResult};
+use std::future::Future;
+use teloxide::Bot;
+use teloxide::payloads::SendMessageSetters;
+use teloxide::prelude::ChatId;
+use teloxide::requests::Requester;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+/// حداکثر تعداد نتایج جستجو که به صورت دکمه نشان داده می‌شود
+const MAX_MATCHES: usize = 10;
+
+/// ====== جستجو و انتخاب محصول (ویرایش، حذف، گالری) ======
+///
+/// جستجو با شناسه، نام یا بارکد؛ یک نتیجه مستقیماً به `on_single` می‌رود و چند نتیجه به صورت
+/// دکمه‌هایی با داده‌ی `{pick_prefix}:{id}` نمایش داده می‌شود (با ردیف انصراف اگر `cancel_data` باشد).
+/// برمی‌گرداند: آیا چیزی پیدا شد
+pub async fn pick_product<F, Fut>(
+    bot: &Bot,
+    client: &TorobClient,
+    chat_id: ChatId,
+    query: &str,
+    pick_prefix: &str,
+    cancel_data: Option<&str>,
+    on_single: F,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync + 'static>>
+where
+    F: FnOnce(Product) -> Fut,
+    Fut: Future<Output = HandlerResult>,
+{
+    let mut found = match client.find_products(query).await {
+        Ok(found) => found,
+        Err(e) => {
+            report(bot, chat_id, "finding products", e).await?;
+            return Ok(false);
+        }
+    };
+
+    match found.len() {
+        0 => {
+            bot.send_message(
+                chat_id,
+                "محصولی با این مشخصات پیدا نشد؛ دوباره تلاش کنید یا /cancel بفرستید.",
+            )
+            .await?;
+            Ok(false)
+        }
+        1 => {
+            on_single(found.remove(0)).await?;
+            Ok(true)
+        }
+        _ => {
+            bot.send_message(
+                chat_id,
+                format!("{} محصول پیدا شد؛ یکی را انتخاب کنید:", found.len()),
+            )
+            .reply_markup(matches_keyboard(&found, pick_prefix, cancel_data))
+            .await?;
+            Ok(true)
+        }
+    }
+}
+
+fn matches_keyboard(found: &[Product], pick_prefix: &str, cancel_data: Option<&str>) -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = found
+        .iter()
+        .take(MAX_MATCHES)
+        .map(|p| {
+            vec![InlineKeyboardButton::callback(
+                format!("{} (id: {})", p.fields.name, p.id),
+                format!("{}:{}", pick_prefix, p.id),
+            )]
+        })
+        .collect();
+    if let Some(data) = cancel_data {
+        rows.push(vec![InlineKeyboardButton::callback("انصراف", data.to_string())]);
+    }
+    InlineKeyboardMarkup::new(rows)
+}
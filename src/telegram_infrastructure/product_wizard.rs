@@ -0,0 +1,631 @@
+use crate::services::category_cache::cached_categories;
+use crate::services::category_suggest::{chat_history, remember_choice, suggest_categories};
+use crate::services::models::category::Category;
+use crate::services::models::product::ProductField;
+use crate::telegram_infrastructure::category_picker::{
+    CategoryTree, ChecklistAction, PickerAction, checklist_view, is_category_callback,
+    is_checklist_callback, parse_action, parse_checklist_action, picker_view, search_view,
+    suggestions_view,
+};
+use crate::telegram_infrastructure::category_endpoints::category_wizard;
+use crate::telegram_infrastructure::endpoints::{HandlerResult, MyDialogue};
+use crate::telegram_infrastructure::models::category_draft::CategoryDraft;
+use crate::telegram_infrastructure::models::image_batch::ImageBatch;
+use crate::telegram_infrastructure::models::product_draft::ProductDraft;
+use crate::telegram_infrastructure::models::state::State;
+use crate::telegram_infrastructure::wizard::{Step, Wizard};
+use crate::utilities::text::parse_number;
+use std::sync::{Arc, LazyLock};
+use teloxide::Bot;
+use teloxide::payloads::{AnswerCallbackQuerySetters, EditMessageTextSetters, SendMessageSetters};
+use teloxide::prelude::{CallbackQuery, ChatId, Message};
+use teloxide::requests::Requester;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+/// حداکثر دسته‌های پیشنهادی از روی نام محصول
+const SUGGESTIONS: usize = 4;
+
+/// جریان ساخت محصول: نام ← قیمت ← دسته‌بندی ← (فیلدهای پیشرفته) ← بازبینی و ثبت
+static PRODUCT_WIZARD: LazyLock<Wizard<ProductDraft>> = LazyLock::new(build);
+
+pub fn product_wizard() -> &'static Wizard<ProductDraft> {
+    &PRODUCT_WIZARD
+}
+
+fn build() -> Wizard<ProductDraft> {
+    let mut wizard = Wizard::new(
+        |step, draft| State::CreateProduct { step, draft },
+        |bot, dialogue, draft| Box::pin(show_review(bot, dialogue, draft)),
+    )
+    .return_if(|d: &ProductDraft| d.reviewing)
+    .cancel_text("روند ایجاد محصول کنسل شد.")
+    .done_label("📋 بازبینی و ثبت")
+    .step(Step::new(
+        "name",
+        |_| "نام محصول را وارد کنید".into(),
+        |d: &mut ProductDraft, text| {
+            ProductField::Name
+                .apply(&mut d.product, text)
+                .map_err(|_| "نام خالی است؛ لطفاً دوباره نام محصول را وارد کنید.".to_string())
+        },
+    )
+    .current(|d: &ProductDraft| Some(d.product.name.clone()).filter(|n| !n.is_empty())))
+    .step(Step::new(
+        "price",
+        |_| "قیمت محصول را وارد کنید (فقط عدد).".into(),
+        |d: &mut ProductDraft, text| {
+            let price = parse_number::<u64>(text)
+                .ok_or("قیمت نامعتبر است؛ فقط عدد وارد کنید (مثلاً 250000).")?;
+            d.product.price = Some(price);
+            Ok(())
+        },
+    )
+    .current(|d: &ProductDraft| d.product.price.map(|p| p.to_string())))
+    .step(
+        Step::new(
+            ProductField::MainCategory.key(),
+            |_| "دسته‌بندی را از دکمه‌های بالا انتخاب کنید، یا بخشی از نام (یا شناسه‌ی) آن را بفرستید.".into(),
+            |d: &mut ProductDraft, text| {
+                d.product.main_category =
+                    parse_number::<u64>(text).ok_or("شناسه نامعتبر است؛ از دکمه‌ها انتخاب کنید یا فقط عدد بفرستید.")?;
+                Ok(())
+            },
+        )
+        .load(|d, chat_id| {
+            Box::pin(async move {
+                let cats = load_categories(chat_id).await?;
+                if cats.is_empty() {
+                    return Err("هیچ دسته‌ بندی‌ ای یافت نشد.".to_string());
+                }
+                let tree = CategoryTree::new(&cats);
+
+                // با برگشت به این مرحله، سطح دستهٔ قبلی باز می‌شود؛ وگرنه اول پیشنهادها
+                let suggested = if d.product.main_category == 0 {
+                    suggest_categories(&cats, &d.product.name, &chat_history(chat_id.0), SUGGESTIONS)
+                } else {
+                    Vec::new()
+                };
+                let (text, markup) = if suggested.is_empty() {
+                    let node = tree.get(d.product.main_category).and_then(|c| c.parent);
+                    picker_view(&tree, node, 0)
+                } else {
+                    suggestions_view(&tree, &suggested)
+                };
+                Ok(vec![(text, Some(markup))])
+            })
+        })
+        .current(|d: &ProductDraft| {
+            (d.product.main_category != 0).then(|| {
+                format!(
+                    "{} (id: {})",
+                    d.category_name.as_deref().unwrap_or("—"),
+                    d.product.main_category
+                )
+            })
+        })
+        .validate(|mut d, chat_id| {
+            Box::pin(async move {
+                let cats = load_categories(chat_id).await?;
+                let tree = CategoryTree::new(&cats);
+                let Some(cat) = tree.get(d.product.main_category) else {
+                    return Err("چنین شناسه‌ای در دسته‌بندی‌ها وجود ندارد؛ دوباره تلاش کنید.".into());
+                };
+                if !cat.available {
+                    return Err("این دسته‌بندی فعال نیست؛ دستهٔ دیگری انتخاب کنید.".into());
+                }
+                d.category_name = Some(tree.path_text(cat.id));
+                if let Some(ids) = d.product.other_categories.as_mut() {
+                    ids.retain(|id| *id != cat.id);
+                }
+                Ok(d)
+            })
+        }),
+    )
+    .step(
+        Step::new(
+            ProductField::OtherCategories.key(),
+            |_| "دسته‌بندی‌های دیگر را از چک‌لیست بالا تیک بزنید و «پایان انتخاب» را بزنید (یا شناسه‌ها را با کاما بفرستید).".into(),
+            |d: &mut ProductDraft, text| ProductField::OtherCategories.apply(&mut d.product, text),
+        )
+        .optional()
+        .load(|d, chat_id| {
+            Box::pin(async move {
+                let cats = load_categories(chat_id).await?;
+                let selected = d.product.other_categories.clone().unwrap_or_default();
+                let (text, markup) =
+                    checklist_view(&CategoryTree::new(&cats), d.product.main_category, &selected, 0);
+                Ok(vec![(text, Some(markup))])
+            })
+        })
+        .current(|d: &ProductDraft| ProductField::OtherCategories.value_text(&d.product))
+        .validate(|mut d, chat_id| {
+            Box::pin(async move {
+                let cats = load_categories(chat_id).await?;
+                let tree = CategoryTree::new(&cats);
+                let main = d.product.main_category;
+                if let Some(ids) = d.product.other_categories.as_mut() {
+                    if let Some(bad) = ids.iter().find(|id| !tree.get(**id).is_some_and(|c| c.available)) {
+                        return Err(format!("دسته‌بندی {} وجود ندارد یا فعال نیست.", bad));
+                    }
+                    ids.retain(|id| *id != main);
+                }
+                if d.product.other_categories.as_ref().is_some_and(Vec::is_empty) {
+                    d.product.other_categories = None;
+                }
+                Ok(d)
+            })
+        }),
+    )
+    .step(
+        Step::new(
+            "mode",
+            |d: &ProductDraft| {
+                format!(
+                    "{}\n\nبه بازبینی و ثبت برویم یا فیلدهای بیشتری (توضیحات، موجودی، ابعاد، ...) وارد می‌کنید؟",
+                    draft_summary(d)
+                )
+            },
+            |d: &mut ProductDraft, text| {
+                d.advanced = match text.trim() {
+                    "adv" => true,
+                    "create" => false,
+                    _ => return Err("یکی از گزینه‌ها را انتخاب کنید.".into()),
+                };
+                Ok(())
+            },
+        )
+        .choices(|_| {
+            vec![
+                ("📋 بازبینی و ثبت".into(), "create".into()),
+                ("⚙️ فیلدهای پیشرفته".into(), "adv".into()),
+            ]
+        }),
+    );
+
+    let total = ProductField::ADVANCED.len();
+    for (i, field) in ProductField::ADVANCED.iter().copied().enumerate() {
+        wizard = wizard.step(
+            Step::new(
+                field.key(),
+                move |_| format!("({}/{}) {}", i + 1, total, field.prompt()),
+                move |d: &mut ProductDraft, text| field.apply(&mut d.product, text),
+            )
+            .optional()
+            .current(move |d: &ProductDraft| field.value_text(&d.product))
+            .skip_if(|d: &ProductDraft| !d.advanced),
+        );
+    }
+
+    wizard
+}
+
+async fn load_categories(chat_id: ChatId) -> Result<Arc<Vec<Category>>, String> {
+    cached_categories(&chat_id.0.to_string())
+        .await
+        .map_err(|e| {
+            eprintln!("error in fetching categories: {}", e);
+            format!("❌ خطا در دریافت دسته‌بندی‌ها:\n{}", e.user_message())
+        })
+}
+
+/// خلاصهٔ پیش‌نویس
+pub fn draft_summary(d: &ProductDraft) -> String {
+    format!(
+        "نام: {}\nقیمت: {}\nدسته‌بندی: {} (id: {})",
+        d.product.name,
+        d.product.price.unwrap_or(0),
+        d.category_name.as_deref().unwrap_or("—"),
+        d.product.main_category
+    )
+}
+
+/// پاسخ متنی به مرحلهٔ فعلی
+pub async fn receive_step(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    step_draft: (String, ProductDraft),
+) -> HandlerResult {
+    let (step, draft) = step_draft;
+
+    // در مرحلهٔ دسته‌بندی، متن غیرعددی جستجو است
+    if let Some(text) = msg.text()
+        && is_category_step(&step)
+        && !text.trim_start().starts_with('/')
+        && parse_number::<u64>(text).is_none()
+    {
+        let cats = match load_categories(msg.chat.id).await {
+            Ok(cats) => cats,
+            Err(why) => {
+                bot.send_message(msg.chat.id, why).await?;
+                return Ok(());
+            }
+        };
+        let (text, markup) = search_view(&CategoryTree::new(&cats), text);
+        bot.send_message(msg.chat.id, text).reply_markup(markup).await?;
+        return Ok(());
+    }
+
+    product_wizard()
+        .handle_message(&bot, dialogue, &msg, &step, draft)
+        .await
+}
+
+fn is_category_step(step: &str) -> bool {
+    step == ProductField::MainCategory.key()
+}
+
+/// دکمه‌های مرحلهٔ فعلی (و درخت دسته‌بندی در مرحلهٔ دسته‌بندی)
+pub async fn step_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    step_draft: (String, ProductDraft),
+) -> HandlerResult {
+    let (step, draft) = step_draft;
+    if q.data.as_deref().is_some_and(is_category_callback) {
+        return category_callback(bot, dialogue, q, step, draft).await;
+    }
+    if q.data.as_deref().is_some_and(is_checklist_callback) {
+        return checklist_callback(bot, dialogue, q, step, draft).await;
+    }
+    product_wizard()
+        .handle_callback(&bot, dialogue, &q, &step, draft)
+        .await
+}
+
+/// تیک زدن دسته‌بندی‌های دیگر؛ انتخاب در پیش‌نویس (وضعیت مکالمه) نگه داشته می‌شود
+async fn checklist_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    step: String,
+    mut draft: ProductDraft,
+) -> HandlerResult {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let chat_id = dialogue.chat_id();
+    let action = q.data.as_deref().and_then(parse_checklist_action);
+    let at_checklist = step == ProductField::OtherCategories.key();
+    let (Some(action), true, Some(m)) = (action, at_checklist, q.message.as_ref()) else {
+        return Ok(());
+    };
+
+    let mut selected = draft.product.other_categories.clone().unwrap_or_default();
+    if action == ChecklistAction::Done {
+        bot.edit_message_reply_markup(chat_id, m.id).await?;
+        let input = if selected.is_empty() {
+            "-".to_string()
+        } else {
+            selected.iter().map(u64::to_string).collect::<Vec<_>>().join(",")
+        };
+        return product_wizard()
+            .submit(&bot, dialogue, &step, draft, &input)
+            .await;
+    }
+
+    let cats = match load_categories(chat_id).await {
+        Ok(cats) => cats,
+        Err(why) => {
+            bot.send_message(chat_id, why).await?;
+            return Ok(());
+        }
+    };
+    let page = match action {
+        ChecklistAction::Toggle { id, page } => {
+            if let Some(i) = selected.iter().position(|s| *s == id) {
+                selected.remove(i);
+            } else {
+                selected.push(id);
+            }
+            draft.product.other_categories = (!selected.is_empty()).then(|| selected.clone());
+            dialogue
+                .update(State::CreateProduct { step, draft: draft.clone() })
+                .await?;
+            page
+        }
+        ChecklistAction::Page(page) => page,
+        ChecklistAction::Done => 0,
+    };
+
+    let (text, markup) = checklist_view(
+        &CategoryTree::new(&cats),
+        draft.product.main_category,
+        &selected,
+        page,
+    );
+    bot.edit_message_text(chat_id, m.id, text)
+        .reply_markup(markup)
+        .await?;
+    Ok(())
+}
+
+/// پیمایش و انتخاب دسته‌بندی با دکمه‌ها؛ فقط وقتی wizard در مرحلهٔ دسته‌بندی است
+async fn category_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    step: String,
+    draft: ProductDraft,
+) -> HandlerResult {
+    let chat_id = dialogue.chat_id();
+    let action = q.data.as_deref().and_then(parse_action);
+    let at_category = is_category_step(&step);
+    let (Some(action), true, Some(m)) = (action, at_category, q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+
+    if action == PickerAction::Disabled {
+        bot.answer_callback_query(q.id.clone())
+            .text("این دسته‌بندی غیرفعال است.")
+            .await?;
+        return Ok(());
+    }
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let cats = match load_categories(chat_id).await {
+        Ok(cats) => cats,
+        Err(why) => {
+            bot.send_message(chat_id, why).await?;
+            return Ok(());
+        }
+    };
+    let tree = CategoryTree::new(&cats);
+
+    match action {
+        PickerAction::Open { node, page } => {
+            let (text, markup) = picker_view(&tree, node, page);
+            bot.edit_message_text(chat_id, m.id, text)
+                .reply_markup(markup)
+                .await?;
+            Ok(())
+        }
+        PickerAction::Select(id) if !tree.get(id).is_some_and(|c| c.available) => {
+            // دسته در این فاصله حذف یا غیرفعال شده است
+            let (text, markup) = picker_view(&tree, None, 0);
+            bot.edit_message_text(chat_id, m.id, format!("⚠️ این دسته‌بندی دیگر قابل انتخاب نیست.\n\n{}", text))
+                .reply_markup(markup)
+                .await?;
+            Ok(())
+        }
+        PickerAction::Select(id) => {
+            bot.edit_message_text(chat_id, m.id, format!("📂 {}", tree.path_text(id)))
+                .await?;
+            product_wizard()
+                .submit(&bot, dialogue, &step, draft, &id.to_string())
+                .await
+        }
+        PickerAction::New { parent } => {
+            // ساخت دسته و برگشت به همین مرحله با دستهٔ ساخته‌شده
+            bot.edit_message_reply_markup(chat_id, m.id).await?;
+            category_wizard()
+                .start(&bot, dialogue, CategoryDraft::new(parent, Some((step, draft))))
+                .await
+        }
+        PickerAction::Disabled => Ok(()),
+    }
+}
+
+/// متن صفحهٔ بازبینی: همهٔ فیلدهای پرشده
+pub fn review_text(d: &ProductDraft) -> String {
+    let mut lines = vec![
+        "📋 بازبینی محصول قبل از ثبت".to_string(),
+        "─────────────────────".to_string(),
+    ];
+    for field in ProductField::ALL {
+        let value = match field {
+            ProductField::MainCategory => Some(format!(
+                "{} (id: {})",
+                d.category_name.as_deref().unwrap_or("—"),
+                d.product.main_category
+            )),
+            _ => field.value_text(&d.product),
+        };
+        if let Some(value) = value {
+            lines.push(format!("{}: {}", field.label(), value));
+        }
+    }
+    lines.push("\nاگر همه‌چیز درست است «ثبت نهایی» را بزنید.".into());
+    lines.join("\n")
+}
+
+fn review_keyboard() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback("✅ ثبت نهایی", "review:ok")],
+        vec![
+            InlineKeyboardButton::callback("✏️ ویرایش یک فیلد", "review:f"),
+            InlineKeyboardButton::callback("⬅️ مرحلهٔ قبل", "review:prev"),
+        ],
+        vec![InlineKeyboardButton::callback("❌ انصراف", "review:x")],
+    ])
+}
+
+/// انتخاب فیلد برای ویرایش (دو ستونه) و بازگشت به بازبینی
+fn review_fields_keyboard() -> InlineKeyboardMarkup {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = ProductField::ALL
+        .chunks(2)
+        .map(|pair| {
+            pair.iter()
+                .map(|f| InlineKeyboardButton::callback(f.label(), format!("review:e:{}", f.key())))
+                .collect()
+        })
+        .collect();
+    rows.push(vec![InlineKeyboardButton::callback("↩️ بازگشت", "review:back")]);
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// پایان wizard: نمایش صفحهٔ بازبینی؛ هیچ چیزی هنوز به API فرستاده نشده است
+async fn show_review(bot: Bot, dialogue: MyDialogue, mut draft: ProductDraft) -> HandlerResult {
+    draft.reviewing = true;
+    bot.send_message(dialogue.chat_id(), review_text(&draft))
+        .reply_markup(review_keyboard())
+        .await?;
+    dialogue.update(State::ReviewProduct { draft }).await?;
+    Ok(())
+}
+
+/// متن در صفحهٔ بازبینی: /cancel و /back؛ بقیه با دکمه‌ها
+pub async fn receive_review_text(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    draft: ProductDraft,
+) -> HandlerResult {
+    if msg.text().map(str::trim) == Some("/cancel") {
+        bot.send_message(msg.chat.id, "روند ایجاد محصول کنسل شد.")
+            .await?;
+        dialogue.update(State::Start).await?;
+        return Ok(());
+    }
+    if msg.text().map(str::trim) == Some("/back") {
+        let mut draft = draft;
+        draft.reviewing = false;
+        return product_wizard().ask_last(&bot, dialogue, draft).await;
+    }
+    bot.send_message(msg.chat.id, "لطفاً از دکمه‌های زیر استفاده کنید (یا /cancel).")
+        .await?;
+    show_review(bot, dialogue, draft).await
+}
+
+/// دکمه‌های صفحهٔ بازبینی: `review:ok`، `review:f`، `review:e:{field}`، `review:back`، `review:prev`، `review:x`
+pub async fn review_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    draft: ProductDraft,
+) -> HandlerResult {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let chat_id = dialogue.chat_id();
+    let Some(m) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let data = q.data.as_deref().unwrap_or_default();
+
+    match data {
+        "review:ok" => {
+            // حذف دکمه‌ها قبل از ارسال تا ثبت دوباره با کلیک مجدد رخ ندهد
+            bot.edit_message_text(chat_id, m.id, review_text(&draft)).await?;
+            create_and_ask_image(bot, dialogue, draft).await
+        }
+        "review:f" => {
+            bot.edit_message_text(chat_id, m.id, format!("{}\n\nکدام فیلد را تغییر می‌دهید؟", review_text(&draft)))
+                .reply_markup(review_fields_keyboard())
+                .await?;
+            Ok(())
+        }
+        "review:back" => {
+            bot.edit_message_text(chat_id, m.id, review_text(&draft))
+                .reply_markup(review_keyboard())
+                .await?;
+            Ok(())
+        }
+        "review:prev" => {
+            // برگشت به مسیر عادی مراحل؛ پاسخ‌ها در پیش‌نویس می‌مانند
+            bot.edit_message_reply_markup(chat_id, m.id).await?;
+            let mut draft = draft;
+            draft.reviewing = false;
+            product_wizard().ask_last(&bot, dialogue, draft).await
+        }
+        "review:x" => {
+            bot.edit_message_text(chat_id, m.id, "روند ایجاد محصول کنسل شد.")
+                .await?;
+            dialogue.update(State::Start).await?;
+            Ok(())
+        }
+        _ => {
+            let Some(field) = data.strip_prefix("review:e:").and_then(ProductField::from_key) else {
+                return Ok(());
+            };
+            bot.edit_message_reply_markup(chat_id, m.id).await?;
+            product_wizard()
+                .ask_step(&bot, dialogue, field.key(), draft)
+                .await
+        }
+    }
+}
+
+/// ساخت محصول از پیش‌نویس و رفتن به مرحلهٔ تصویر
+async fn create_and_ask_image(bot: Bot, dialogue: MyDialogue, draft: ProductDraft) -> HandlerResult {
+    let chat_id = dialogue.chat_id();
+
+    // فراخوانی سرویس ایجاد محصول
+    let product_id = match crate::services::product_service::create_product(
+        &draft.product,
+        chat_id.0.to_string(),
+    )
+    .await
+    {
+        Ok(id) => id,
+        Err(e) => {
+            // پیش‌نویس از دست نمی‌رود؛ کاربر می‌تواند فیلد مشکل‌دار را اصلاح کند یا دوباره ثبت کند
+            eprintln!("error in creating product: {}", e);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "❌ خطا در ایجاد محصول:\n{}\n\n{}",
+                    e.user_message(),
+                    review_text(&draft)
+                ),
+            )
+            .reply_markup(review_keyboard())
+            .await?;
+            dialogue.update(State::ReviewProduct { draft }).await?;
+            return Ok(());
+        }
+    };
+
+    crate::services::trash::remember_created(chat_id.0, product_id);
+    remember_choice(chat_id.0, &draft.product.name, draft.product.main_category);
+
+    let summary = format!(
+        "✅ محصول با موفقیت ایجاد شد.\n\
+         ─────────────────────\n\
+         {}\n\
+         🆔 شناسه محصول: {}\
+         \n برای ثبت محصول بعدی روی /start  کلیک کنید",
+        draft_summary(&draft),
+        product_id
+    );
+
+    bot.send_message(chat_id, summary).await?;
+
+    // پیام نهایی به کاربر
+    bot.send_message(
+        chat_id,
+        "تصاویر این محصول را بفرستید؛ می‌توانید چند عکس را یکجا (آلبوم) یا پشت سر هم بفرستید.\n\
+         برای کیفیت بهتر، تصویر را «به صورت فایل» بفرستید تا فشرده نشود؛ لینک تصویر هم پذیرفته می‌شود.\n\
+         کپشن هر عکس متن جایگزین آن است و «⭐️» در کپشن آن را تصویر پیش‌فرض می‌کند.\n\
+         در پایان /done را بزنید.",
+    )
+    .await?;
+
+    dialogue
+        .update(State::ReceiveProductImage {
+            product_id,
+            draft,
+            batch: ImageBatch::default(),
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_product_wizard {
+    use super::*;
+
+    #[test]
+    fn test_review_text_lists_populated_fields() {
+        let mut draft = ProductDraft::default();
+        draft.product.name = "کفش".into();
+        draft.product.main_category = 7;
+        draft.category_name = Some("پوشاک".into());
+        draft.product.barcode = Some("123".into());
+
+        let text = review_text(&draft);
+        assert!(text.contains("پوشاک (id: 7)"));
+        assert!(text.contains("بارکد: 123"));
+        assert!(!text.contains(ProductField::Guarantee.label()));
+        assert!(product_wizard().steps().iter().any(|s| s.id == "main_category"));
+    }
+}
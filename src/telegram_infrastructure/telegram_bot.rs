@@ -1,7 +1,8 @@
+use std::sync::Arc;
 use teloxide::{dptree, Bot};
 use teloxide::requests::Request;
-use teloxide::dispatching::dialogue::InMemStorage;
-use teloxide::prelude::{Message, Requester, Update};
+use teloxide::dispatching::dialogue::{ErasedStorage, InMemStorage, Storage};
+use teloxide::prelude::{CallbackQuery, Message, Requester, Update};
 use crate::telegram_infrastructure::models::state::State;
 use crate::telegram_infrastructure::models::command::Command;
 use teloxide::dispatching::{Dispatcher, HandlerExt, UpdateFilterExt};
@@ -10,14 +11,23 @@ pub type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync + 's
 
 pub struct TelegramBot {
     bot: Bot,
+    /// محل ذخیرهٔ وضعیت مکالمه‌ها؛ پیش‌فرض درون حافظه
+    storage: Arc<ErasedStorage<State>>,
 }
 
 impl TelegramBot {
     pub fn new(token: String) -> Self {
         Self {
             bot: Bot::new(token),
+            storage: InMemStorage::<State>::new().erase(),
         }
     }
+
+    /// استفاده از یک محل ذخیرهٔ ماندگار (SQLite/Redis) برای وضعیت مکالمه‌ها
+    pub fn with_dialogue_storage(mut self, storage: Arc<ErasedStorage<State>>) -> Self {
+        self.storage = storage;
+        self
+    }
     
     pub async fn run_dispatcher(&self) -> HandlerResult
     {
@@ -25,38 +35,85 @@ impl TelegramBot {
 
         bot_clone.get_me().send().await?;
 
+        let messages = Update::filter_message()
+            .enter_dialogue::<Message, ErasedStorage<State>, State>()
+            .branch(dptree::case![State::Unreadable]
+                .endpoint(crate::telegram_infrastructure::endpoints::unreadable_state))
+            .branch(
+                dptree::case![State::Start]
+                    .branch(dptree::entry().filter_command::<Command>()
+                        .endpoint(crate::telegram_infrastructure::endpoints::start)),
+            )
+            .branch(dptree::case![State::ReceiveWebSite]
+                .endpoint(crate::telegram_infrastructure::endpoints::receive_website))
+            .branch(dptree::case![State::ReceiveToken]
+                .endpoint(crate::telegram_infrastructure::endpoints::receive_token))
+            .branch(dptree::case![State::CreateProduct { step, draft }]
+                .endpoint(crate::telegram_infrastructure::product_wizard::receive_step))
+            .branch(dptree::case![State::ReviewProduct { draft }]
+                .endpoint(crate::telegram_infrastructure::product_wizard::receive_review_text))
+            .branch(dptree::case![State::ReceiveProductImage { product_id, draft, batch }]
+                .endpoint(crate::telegram_infrastructure::image_endpoints::receive_product_image))
+            .branch(dptree::case![State::EditFindProduct]
+                .endpoint(crate::telegram_infrastructure::edit_endpoints::receive_edit_query))
+            .branch(dptree::case![State::EditChooseField { product_id }]
+                .endpoint(crate::telegram_infrastructure::edit_endpoints::receive_edit_choice_text))
+            .branch(dptree::case![State::EditReceiveValue { product_id, field }]
+                .endpoint(crate::telegram_infrastructure::edit_endpoints::receive_edit_value))
+            .branch(dptree::case![State::DeleteFindProduct]
+                .endpoint(crate::telegram_infrastructure::delete_endpoints::receive_delete_query))
+            .branch(dptree::case![State::GalleryFindProduct]
+                .endpoint(crate::telegram_infrastructure::gallery_endpoints::receive_gallery_query))
+            .branch(dptree::case![State::GalleryReplaceImage { product_id, image_id }]
+                .endpoint(crate::telegram_infrastructure::gallery_endpoints::receive_replacement))
+            .branch(dptree::case![State::GalleryEditAlt { product_id, image_id }]
+                .endpoint(crate::telegram_infrastructure::gallery_endpoints::receive_alt))
+            .branch(dptree::case![State::CreateCategory { step, draft }]
+                .endpoint(crate::telegram_infrastructure::category_endpoints::receive_step))
+            .branch(dptree::case![State::RenameCategory { category_id }]
+                .endpoint(crate::telegram_infrastructure::category_endpoints::receive_new_name));
+
+        // دکمه‌های شیشه‌ای (inline keyboard)
+        let callbacks = Update::filter_callback_query()
+            // وضعیت ذخیره‌شدهٔ ناخوانا قبل از هر دکمه‌ای به Start برمی‌گردد
+            .branch(dptree::entry()
+                .enter_dialogue::<CallbackQuery, ErasedStorage<State>, State>()
+                .branch(dptree::case![State::Unreadable]
+                    .endpoint(crate::telegram_infrastructure::endpoints::unreadable_state_callback)))
+            // لیست محصولات به وضعیت مکالمه وابسته نیست
+            .branch(dptree::filter(crate::telegram_infrastructure::list_endpoints::is_listing_callback)
+                .endpoint(crate::telegram_infrastructure::list_endpoints::listing_callback))
+            .branch(dptree::filter(crate::telegram_infrastructure::delete_endpoints::is_delete_callback)
+                .endpoint(crate::telegram_infrastructure::delete_endpoints::delete_callback))
+            // مدیریت دسته‌ها از هر وضعیتی؛ تغییر نام و زیردستهٔ جدید فقط در Start (`ensure_idle`)
+            .branch(dptree::filter(crate::telegram_infrastructure::category_endpoints::is_manage_callback)
+                .enter_dialogue::<CallbackQuery, ErasedStorage<State>, State>()
+                .endpoint(crate::telegram_infrastructure::category_endpoints::manage_callback))
+            // گالری تصاویر هم مستقل از وضعیت است؛ جایگزینی و ویرایش alt فقط در Start (`ensure_idle`)
+            .branch(dptree::filter(crate::telegram_infrastructure::gallery_endpoints::is_gallery_callback)
+                .enter_dialogue::<CallbackQuery, ErasedStorage<State>, State>()
+                .endpoint(crate::telegram_infrastructure::gallery_endpoints::gallery_callback))
+            .branch(dptree::entry()
+                .enter_dialogue::<CallbackQuery, ErasedStorage<State>, State>()
+                .branch(dptree::case![State::CreateProduct { step, draft }]
+                    .endpoint(crate::telegram_infrastructure::product_wizard::step_callback))
+                .branch(dptree::case![State::ReviewProduct { draft }]
+                    .endpoint(crate::telegram_infrastructure::product_wizard::review_callback))
+                .branch(dptree::case![State::CreateCategory { step, draft }]
+                    .endpoint(crate::telegram_infrastructure::category_endpoints::step_callback))
+                .branch(dptree::case![State::ReceiveProductImage { product_id, draft, batch }]
+                    .endpoint(crate::telegram_infrastructure::image_endpoints::image_callback))
+                .branch(dptree::case![State::EditFindProduct]
+                    .endpoint(crate::telegram_infrastructure::edit_endpoints::pick_product_callback))
+                .branch(dptree::case![State::EditChooseField { product_id }]
+                    .endpoint(crate::telegram_infrastructure::edit_endpoints::choose_field_callback))
+                .branch(dptree::endpoint(crate::telegram_infrastructure::endpoints::stale_callback)));
+
         Dispatcher::builder(
             bot_clone,
-            Update::filter_message()
-                .enter_dialogue::<Message, InMemStorage<State>, State>()
-                .branch(
-                    dptree::case![State::Start]
-                        .branch(dptree::entry().filter_command::<Command>()
-                            .endpoint(crate::telegram_infrastructure::endpoints::start)),
-                )
-                .branch(dptree::case![State::ReceiveWebSite]
-                    .endpoint(crate::telegram_infrastructure::endpoints::receive_website))
-                .branch(dptree::case![State::ReceiveToken]
-                    .endpoint(crate::telegram_infrastructure::endpoints::receive_token))
-                .branch(dptree::case![State::ReceiveProductName]
-                    .endpoint(crate::telegram_infrastructure::endpoints::receive_name))
-                .branch(dptree::case![State::ReceivePrice { name }]
-                    .endpoint(crate::telegram_infrastructure::endpoints::receive_price))
-                .branch(dptree::case![State::ReceiveCategoryId { name, price }]
-                        .endpoint(crate::telegram_infrastructure::endpoints::receive_category_id),
-                )
-                .branch(
-                    dptree::case![State::ReceiveProductImage {
-                    name,
-                    price,
-                    category_id,
-                    category_name,
-                    product_id
-                }]
-                        .endpoint(crate::telegram_infrastructure::endpoints::receive_product_image),
-                ),
+            dptree::entry().branch(messages).branch(callbacks),
         )
-            .dependencies(dptree::deps![InMemStorage::<State>::new()])
+            .dependencies(dptree::deps![self.storage.clone()])
             .enable_ctrlc_handler()
             .build()
             .dispatch()
@@ -0,0 +1,553 @@
+use crate::telegram_infrastructure::endpoints::{HandlerResult, MyDialogue};
+use crate::telegram_infrastructure::models::state::State;
+use futures::future::BoxFuture;
+use teloxide::Bot;
+use teloxide::payloads::SendMessageSetters;
+use teloxide::prelude::{CallbackQuery, ChatId, Message};
+use teloxide::requests::Requester;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+/// پیشوند دکمه‌های wizard (`wz:{شناسهٔ مرحله}:{مقدار}`)؛ مقدار مثل متن کاربر پردازش می‌شود
+const CALLBACK_PREFIX: &str = "wz:";
+
+type PromptFn<D> = Box<dyn Fn(&D) -> String + Send + Sync>;
+type ParseFn<D> = Box<dyn Fn(&mut D, &str) -> Result<(), String> + Send + Sync>;
+type ChoicesFn<D> = Box<dyn Fn(&D) -> Vec<(String, String)> + Send + Sync>;
+type RuleFn<D> = Box<dyn Fn(&D) -> bool + Send + Sync>;
+type CurrentFn<D> = Box<dyn Fn(&D) -> Option<String> + Send + Sync>;
+/// اعتبارسنجی/تکمیل async (مثلاً با API)؛ پیش‌نویس به‌روزشده یا پیام خطا
+type ValidateFn<D> = Box<dyn Fn(D, ChatId) -> BoxFuture<'static, Result<D, String>> + Send + Sync>;
+/// پیام مقدماتی: متن و دکمه‌های اختیاری
+pub type Intro = (String, Option<InlineKeyboardMarkup>);
+/// پیام‌های مقدماتی async قبل از سؤال (مثلاً درخت دسته‌بندی‌ها)
+type LoadFn<D> = Box<dyn Fn(D, ChatId) -> BoxFuture<'static, Result<Vec<Intro>, String>> + Send + Sync>;
+type FinishFn<D> = Box<dyn Fn(Bot, MyDialogue, D) -> BoxFuture<'static, HandlerResult> + Send + Sync>;
+
+/// ====== یک مرحله از wizard ======
+///
+/// سؤال، تبدیل/اعتبارسنجی پاسخ، قابل رد شدن بودن و قانون پرش خودکار.
+pub struct Step<D> {
+    pub id: &'static str,
+    prompt: PromptFn<D>,
+    parse: ParseFn<D>,
+    validate: Option<ValidateFn<D>>,
+    load: Option<LoadFn<D>>,
+    choices: Option<ChoicesFn<D>>,
+    skip_if: Option<RuleFn<D>>,
+    current: Option<CurrentFn<D>>,
+    optional: bool,
+}
+
+impl<D> Step<D> {
+    pub fn new(
+        id: &'static str,
+        prompt: impl Fn(&D) -> String + Send + Sync + 'static,
+        parse: impl Fn(&mut D, &str) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            id,
+            prompt: Box::new(prompt),
+            parse: Box::new(parse),
+            validate: None,
+            load: None,
+            choices: None,
+            skip_if: None,
+            current: None,
+            optional: false,
+        }
+    }
+
+    /// کاربر می‌تواند با /skip از این مرحله بگذرد
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    /// اگر شرط برقرار باشد مرحله اصلاً پرسیده نمی‌شود
+    pub fn skip_if(mut self, rule: impl Fn(&D) -> bool + Send + Sync + 'static) -> Self {
+        self.skip_if = Some(Box::new(rule));
+        self
+    }
+
+    /// پاسخ قبلی این مرحله در پیش‌نویس (بعد از برگشت نمایش داده می‌شود و با /keep حفظ می‌شود)
+    pub fn current(mut self, f: impl Fn(&D) -> Option<String> + Send + Sync + 'static) -> Self {
+        self.current = Some(Box::new(f));
+        self
+    }
+
+    /// دکمه‌های پاسخ آماده: (متن دکمه، مقداری که به جای متن کاربر پردازش می‌شود)
+    pub fn choices(mut self, f: impl Fn(&D) -> Vec<(String, String)> + Send + Sync + 'static) -> Self {
+        self.choices = Some(Box::new(f));
+        self
+    }
+
+    /// اعتبارسنجی async بعد از `parse`
+    pub fn validate(
+        mut self,
+        f: impl Fn(D, ChatId) -> BoxFuture<'static, Result<D, String>> + Send + Sync + 'static,
+    ) -> Self {
+        self.validate = Some(Box::new(f));
+        self
+    }
+
+    /// پیام‌هایی که قبل از سؤال فرستاده می‌شوند
+    pub fn load(
+        mut self,
+        f: impl Fn(D, ChatId) -> BoxFuture<'static, Result<Vec<Intro>, String>> + Send + Sync + 'static,
+    ) -> Self {
+        self.load = Some(Box::new(f));
+        self
+    }
+
+    fn is_skipped(&self, draft: &D) -> bool {
+        self.skip_if.as_ref().is_some_and(|rule| rule(draft))
+    }
+}
+
+/// ====== wizard چندمرحله‌ای ======
+///
+/// یک جریان به صورت لیست مرتب `Step` تعریف می‌شود؛ همهٔ کارهای تکراری
+/// (خواندن متن، /cancel، /back، /keep، /skip، /done، خطای اعتبارسنجی، `dialogue.update`) همین‌جا انجام می‌شود.
+pub struct Wizard<D> {
+    steps: Vec<Step<D>>,
+    /// ساخت `State` برای شناسهٔ مرحلهٔ فعلی و پیش‌نویس
+    ///
+    /// شناسه (نه اندیس) ذخیره می‌شود تا اضافه/جابه‌جا شدن مراحل وضعیت‌های ذخیره‌شده را خراب نکند.
+    to_state: fn(String, D) -> State,
+    finish: FinishFn<D>,
+    /// اگر برقرار باشد، بعد از هر پاسخ مستقیم به پایان برمی‌گردد (مثلاً صفحهٔ بازبینی)
+    return_if: Option<RuleFn<D>>,
+    /// به جای پیام پیش‌فرض و رفتن به `State::Start` (مثلاً برگشت به جریان والد)
+    on_cancel: Option<FinishFn<D>>,
+    cancel_text: &'static str,
+    done_label: &'static str,
+}
+
+impl<D: Clone + Send + Sync + 'static> Wizard<D> {
+    pub fn new(
+        to_state: fn(String, D) -> State,
+        finish: impl Fn(Bot, MyDialogue, D) -> BoxFuture<'static, HandlerResult> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            steps: Vec::new(),
+            to_state,
+            finish: Box::new(finish),
+            return_if: None,
+            on_cancel: None,
+            cancel_text: "روند کنسل شد.",
+            done_label: "✅ پایان",
+        }
+    }
+
+    pub fn step(mut self, step: Step<D>) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    pub fn return_if(mut self, rule: impl Fn(&D) -> bool + Send + Sync + 'static) -> Self {
+        self.return_if = Some(Box::new(rule));
+        self
+    }
+
+    pub fn on_cancel(
+        mut self,
+        f: impl Fn(Bot, MyDialogue, D) -> BoxFuture<'static, HandlerResult> + Send + Sync + 'static,
+    ) -> Self {
+        self.on_cancel = Some(Box::new(f));
+        self
+    }
+
+    pub fn cancel_text(mut self, text: &'static str) -> Self {
+        self.cancel_text = text;
+        self
+    }
+
+    pub fn done_label(mut self, label: &'static str) -> Self {
+        self.done_label = label;
+        self
+    }
+
+    pub fn steps(&self) -> &[Step<D>] {
+        &self.steps
+    }
+
+    /// شروع از اولین مرحله
+    pub async fn start(&self, bot: &Bot, dialogue: MyDialogue, draft: D) -> HandlerResult {
+        self.ask(bot, dialogue, 0, draft).await
+    }
+
+    /// پرسیدن اولین مرحلهٔ پرش‌نخورده از `index` به بعد؛ اگر مرحله‌ای نماند، پایان
+    ///
+    /// اگر بارگذاری پیام‌های مقدماتی خطا بدهد، وضعیت تغییر نمی‌کند تا کاربر دوباره تلاش کند.
+    pub async fn ask(&self, bot: &Bot, dialogue: MyDialogue, index: usize, draft: D) -> HandlerResult {
+        let Some(index) = self.next_index(index, &draft) else {
+            return (self.finish)(bot.clone(), dialogue, draft).await;
+        };
+        self.ask_at(bot, dialogue, index, draft).await
+    }
+
+    /// پرسیدن مستقیم یک مرحله با شناسه (بدون در نظر گرفتن `skip_if`)
+    pub async fn ask_step(&self, bot: &Bot, dialogue: MyDialogue, id: &str, draft: D) -> HandlerResult {
+        match self.position(id) {
+            Some(index) => self.ask_at(bot, dialogue, index, draft).await,
+            None => (self.finish)(bot.clone(), dialogue, draft).await,
+        }
+    }
+
+    /// مرحلهٔ ذخیره‌شده دیگر وجود ندارد (مثلاً بعد از به‌روزرسانی ربات)؛ از اول با همان پیش‌نویس
+    async fn restart(&self, bot: &Bot, dialogue: MyDialogue, draft: D) -> HandlerResult {
+        bot.send_message(dialogue.chat_id(), "این مرحله دیگر وجود ندارد؛ از ابتدا ادامه می‌دهیم.")
+            .await?;
+        self.start(bot, dialogue, draft).await
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.steps.iter().position(|s| s.id == id)
+    }
+
+    /// برگشت از پایان (مثلاً صفحهٔ بازبینی) به آخرین مرحلهٔ پرش‌نخورده
+    pub async fn ask_last(&self, bot: &Bot, dialogue: MyDialogue, draft: D) -> HandlerResult {
+        match self.prev_index(self.steps.len(), &draft) {
+            Some(index) => self.ask_at(bot, dialogue, index, draft).await,
+            None => self.start(bot, dialogue, draft).await,
+        }
+    }
+
+    async fn ask_at(&self, bot: &Bot, dialogue: MyDialogue, index: usize, draft: D) -> HandlerResult {
+        let step = &self.steps[index];
+        let chat_id = dialogue.chat_id();
+
+        if let Some(load) = &step.load {
+            match load(draft.clone(), chat_id).await {
+                Ok(messages) => {
+                    for (text, markup) in messages {
+                        match markup {
+                            Some(markup) => bot.send_message(chat_id, text).reply_markup(markup).await?,
+                            None => bot.send_message(chat_id, text).await?,
+                        };
+                    }
+                }
+                Err(why) => {
+                    bot.send_message(chat_id, why).await?;
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut prompt = (step.prompt)(&draft);
+        if step.optional {
+            prompt.push_str("\nبرای رد شدن /skip");
+            if self.rest_is_optional(index, &draft) {
+                prompt.push_str(" و برای پایان /done");
+            }
+        }
+        if let Some(current) = step.current.as_ref().and_then(|f| f(&draft)) {
+            prompt.push_str(&format!("\nپاسخ قبلی: {} (برای نگه داشتن /keep)", current));
+        }
+        if self.can_go_back(index, &draft) {
+            prompt.push_str("\nبرای مرحلهٔ قبل /back");
+        }
+
+        let keyboard = self.keyboard(index, &draft);
+        if keyboard.inline_keyboard.is_empty() {
+            bot.send_message(chat_id, prompt).await?;
+        } else {
+            bot.send_message(chat_id, prompt).reply_markup(keyboard).await?;
+        }
+
+        dialogue.update((self.to_state)(step.id.to_string(), draft)).await?;
+        Ok(())
+    }
+
+    /// پاسخ متنی کاربر به مرحلهٔ `step`
+    pub async fn handle_message(
+        &self,
+        bot: &Bot,
+        dialogue: MyDialogue,
+        msg: &Message,
+        step: &str,
+        draft: D,
+    ) -> HandlerResult {
+        let Some(index) = self.position(step) else {
+            return self.restart(bot, dialogue, draft).await;
+        };
+        let Some(text) = msg.text() else {
+            bot.send_message(msg.chat.id, "لطفاً پاسخ را به صورت متن بفرستید.")
+                .await?;
+            return Ok(());
+        };
+        self.handle_input(bot, dialogue, index, draft, text).await
+    }
+
+    /// دکمه‌های `wz:*` در مرحلهٔ `step`
+    pub async fn handle_callback(
+        &self,
+        bot: &Bot,
+        dialogue: MyDialogue,
+        q: &CallbackQuery,
+        step: &str,
+        draft: D,
+    ) -> HandlerResult {
+        bot.answer_callback_query(q.id.clone()).await?;
+        let Some(index) = self.position(step) else {
+            return self.restart(bot, dialogue, draft).await;
+        };
+        // دکمهٔ مرحله‌ای دیگر (پیام قدیمی) نادیده گرفته می‌شود
+        let Some(value) = q
+            .data
+            .as_deref()
+            .and_then(|d| d.strip_prefix(CALLBACK_PREFIX))
+            .and_then(|d| d.split_once(':'))
+            .filter(|(id, _)| *id == step)
+            .map(|(_, value)| value.to_string())
+        else {
+            return Ok(());
+        };
+        if let Some(m) = &q.message {
+            // دکمه‌های پیام قبلی دیگر معتبر نیستند
+            bot.edit_message_reply_markup(dialogue.chat_id(), m.id).await?;
+        }
+        self.handle_input(bot, dialogue, index, draft, &value).await
+    }
+
+    /// پردازش یک مقدار از بیرون wizard (مثلاً دکمه‌های اختصاصی یک مرحله) مثل متن کاربر
+    pub async fn submit(
+        &self,
+        bot: &Bot,
+        dialogue: MyDialogue,
+        step: &str,
+        draft: D,
+        input: &str,
+    ) -> HandlerResult {
+        match self.position(step) {
+            Some(index) => self.handle_input(bot, dialogue, index, draft, input).await,
+            None => self.restart(bot, dialogue, draft).await,
+        }
+    }
+
+    async fn handle_input(
+        &self,
+        bot: &Bot,
+        dialogue: MyDialogue,
+        index: usize,
+        mut draft: D,
+        input: &str,
+    ) -> HandlerResult {
+        let chat_id = dialogue.chat_id();
+        let Some(step) = self.steps.get(index) else {
+            return (self.finish)(bot.clone(), dialogue, draft).await;
+        };
+
+        match input.trim().to_lowercase().as_str() {
+            "/cancel" => {
+                if let Some(on_cancel) = &self.on_cancel {
+                    return on_cancel(bot.clone(), dialogue, draft).await;
+                }
+                bot.send_message(chat_id, self.cancel_text).await?;
+                dialogue.update(State::Start).await?;
+                return Ok(());
+            }
+            "/back" if self.return_if.as_ref().is_some_and(|rule| rule(&draft)) => {
+                return (self.finish)(bot.clone(), dialogue, draft).await;
+            }
+            "/back" => {
+                return match self.prev_index(index, &draft) {
+                    Some(prev) => self.ask_at(bot, dialogue, prev, draft).await,
+                    None => {
+                        bot.send_message(chat_id, "این اولین مرحله است.").await?;
+                        Ok(())
+                    }
+                };
+            }
+            "/keep" if step.current.as_ref().and_then(|f| f(&draft)).is_some() => {
+                return self.advance(bot, dialogue, index, draft).await;
+            }
+            "/skip" if step.optional => return self.advance(bot, dialogue, index, draft).await,
+            "/done" if self.rest_is_optional(index, &draft) => {
+                return (self.finish)(bot.clone(), dialogue, draft).await;
+            }
+            "/skip" | "/done" => {
+                bot.send_message(chat_id, "این مرحله اجباری است؛ لطفاً پاسخ دهید.")
+                    .await?;
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if let Err(why) = (step.parse)(&mut draft, input) {
+            bot.send_message(chat_id, why).await?;
+            return Ok(());
+        }
+
+        if let Some(validate) = &step.validate {
+            draft = match validate(draft, chat_id).await {
+                Ok(d) => d,
+                Err(why) => {
+                    bot.send_message(chat_id, why).await?;
+                    return Ok(());
+                }
+            };
+        }
+
+        self.advance(bot, dialogue, index, draft).await
+    }
+
+    /// رفتن به مرحلهٔ بعد از `index` یا برگشت به پایان طبق `return_if`
+    async fn advance(&self, bot: &Bot, dialogue: MyDialogue, index: usize, draft: D) -> HandlerResult {
+        if self.return_if.as_ref().is_some_and(|rule| rule(&draft)) {
+            return (self.finish)(bot.clone(), dialogue, draft).await;
+        }
+        self.ask(bot, dialogue, index + 1, draft).await
+    }
+
+    fn next_index(&self, from: usize, draft: &D) -> Option<usize> {
+        (from..self.steps.len()).find(|&i| !self.steps[i].is_skipped(draft))
+    }
+
+    /// آخرین مرحلهٔ پرش‌نخورده قبل از `index`
+    fn prev_index(&self, index: usize, draft: &D) -> Option<usize> {
+        (0..index.min(self.steps.len()))
+            .rev()
+            .find(|&i| !self.steps[i].is_skipped(draft))
+    }
+
+    fn can_go_back(&self, index: usize, draft: &D) -> bool {
+        self.return_if.as_ref().is_some_and(|rule| rule(draft)) || self.prev_index(index, draft).is_some()
+    }
+
+    /// آیا این مرحله و همهٔ مراحل بعدی (پرش‌نخورده) اختیاری‌اند
+    fn rest_is_optional(&self, index: usize, draft: &D) -> bool {
+        self.steps[index..]
+            .iter()
+            .all(|s| s.optional || s.is_skipped(draft))
+    }
+
+    fn keyboard(&self, index: usize, draft: &D) -> InlineKeyboardMarkup {
+        let step = &self.steps[index];
+        let mut rows: Vec<Vec<InlineKeyboardButton>> = step
+            .choices
+            .as_ref()
+            .map(|f| f(draft))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(label, value)| {
+                vec![InlineKeyboardButton::callback(
+                    label,
+                    format!("{}{}:{}", CALLBACK_PREFIX, step.id, value),
+                )]
+            })
+            .collect();
+
+        let mut controls: Vec<InlineKeyboardButton> = Vec::new();
+        if step.optional {
+            controls.push(InlineKeyboardButton::callback(
+                "⏭ رد شدن",
+                format!("{}{}:/skip", CALLBACK_PREFIX, step.id),
+            ));
+            if self.rest_is_optional(index, draft) {
+                controls.push(InlineKeyboardButton::callback(
+                    self.done_label,
+                    format!("{}{}:/done", CALLBACK_PREFIX, step.id),
+                ));
+            }
+        }
+        if !controls.is_empty() {
+            rows.push(controls);
+        }
+
+        let mut nav: Vec<InlineKeyboardButton> = Vec::new();
+        if self.can_go_back(index, draft) {
+            nav.push(InlineKeyboardButton::callback(
+                "⬅️ مرحلهٔ قبل",
+                format!("{}{}:/back", CALLBACK_PREFIX, step.id),
+            ));
+        }
+        if step.current.as_ref().and_then(|f| f(draft)).is_some() {
+            nav.push(InlineKeyboardButton::callback(
+                "✅ نگه داشتن پاسخ قبلی",
+                format!("{}{}:/keep", CALLBACK_PREFIX, step.id),
+            ));
+        }
+        if !nav.is_empty() {
+            rows.push(nav);
+        }
+        InlineKeyboardMarkup::new(rows)
+    }
+}
+
+#[cfg(test)]
+mod test_wizard {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Draft {
+        name: String,
+        extra: bool,
+        note: Option<String>,
+    }
+
+    fn wizard() -> Wizard<Draft> {
+        Wizard::new(|_, _| State::Start, |_, _, _| Box::pin(async { Ok(()) }))
+            .step(Step::new("name", |_| "name?".into(), |d: &mut Draft, t| {
+                d.name = t.to_string();
+                Ok(())
+            }))
+            .step(Step::new("extra", |_| "extra?".into(), |d: &mut Draft, t| {
+                d.extra = t == "yes";
+                Ok(())
+            }))
+            .step(
+                Step::new("note", |_| "note?".into(), |d: &mut Draft, t| {
+                    d.note = Some(t.to_string());
+                    Ok(())
+                })
+                .optional()
+                .skip_if(|d: &Draft| !d.extra),
+            )
+    }
+
+    #[test]
+    fn test_skip_rules_and_optional_tail() {
+        let w = wizard();
+        let mut draft = Draft::default();
+
+        assert_eq!(w.next_index(2, &draft), None);
+        assert!(!w.rest_is_optional(1, &draft));
+
+        draft.extra = true;
+        assert_eq!(w.next_index(2, &draft), Some(2));
+        assert!(w.rest_is_optional(2, &draft));
+        assert_eq!(w.keyboard(2, &draft).inline_keyboard[0].len(), 2);
+        assert!(w.keyboard(0, &draft).inline_keyboard.is_empty());
+    }
+
+    #[test]
+    fn test_callbacks_carry_step_id() {
+        let w = wizard();
+        let draft = Draft {
+            extra: true,
+            ..Draft::default()
+        };
+        let skip = &w.keyboard(2, &draft).inline_keyboard[0][0];
+        assert!(matches!(
+            &skip.kind,
+            teloxide::types::InlineKeyboardButtonKind::CallbackData(data) if data == "wz:note:/skip"
+        ));
+        assert_eq!(w.position("note"), Some(2));
+        // شناسهٔ مرحله‌ای که حذف شده، جریان را از ابتدا شروع می‌کند
+        assert_eq!(w.position("removed"), None);
+    }
+
+    #[test]
+    fn test_back_skips_hidden_steps() {
+        let w = wizard();
+        let mut draft = Draft::default();
+
+        assert_eq!(w.prev_index(0, &draft), None);
+        assert_eq!(w.prev_index(3, &draft), Some(1));
+        draft.extra = true;
+        assert_eq!(w.prev_index(3, &draft), Some(2));
+        // دکمهٔ «مرحلهٔ قبل» در ردیف جدا
+        assert_eq!(w.keyboard(1, &draft).inline_keyboard.len(), 1);
+    }
+}
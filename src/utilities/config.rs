@@ -0,0 +1,605 @@
+use crate::utilities::crypto::{parse_key, TokenCipher};
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// مسیر پیش‌فرض فایل تنظیمات (اگر `TOROB_BOT_CONFIG` ست نشده باشد)
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+
+/// متغیر محیطی برای تعیین مسیر فایل تنظیمات
+pub const CONFIG_PATH_ENV: &str = "TOROB_BOT_CONFIG";
+
+/// سقف حجم فایلی که Bot API اجازهٔ دانلود آن را می‌دهد (20 مگابایت)
+const TELEGRAM_DOWNLOAD_LIMIT: u64 = 20 * 1024 * 1024;
+
+const LOG_LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// ====== تنظیمات اجرای ربات ======
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    /// توکن ربات تلگرام (اجباری)
+    pub bot_token: String,
+    /// آدرس پیش‌فرض پنل؛ اگر ست شود کاربر فقط توکن را وارد می‌کند
+    pub default_panel_url: Option<String>,
+    /// سطح لاگ کتابخانه‌ها (teloxide و ...): off, error, warn, info, debug, trace؛ پیام‌های خود برنامه با `eprintln!` همیشه چاپ می‌شوند
+    pub log_level: String,
+    pub images: ImageConfig,
+    pub http: HttpConfig,
+    pub storage: StorageConfig,
+    pub dialogue: DialogueConfig,
+    pub trash: TrashConfig,
+    pub categories: CategoriesConfig,
+}
+
+/// محدودیت‌ها و پردازش تصویر محصول در `receive_product_image`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageConfig {
+    /// قالب‌های ورودی مجاز (از روی امضای فایل)
+    pub allowed_extensions: Vec<String>,
+    /// سقف حجم تصویر پردازش‌شده‌ای که به پنل فرستاده می‌شود
+    pub max_size_bytes: u64,
+    /// سقف حجم فایلی که کاربر می‌فرستد (قبل از کوچک‌سازی)
+    pub max_input_bytes: u64,
+    /// بزرگ‌ترین ضلع تصویر بعد از کوچک‌سازی (پیکسل)
+    pub max_dimension: u32,
+    /// کیفیت اولیهٔ JPEG (۱ تا ۱۰۰)؛ اگر حجم جا نشود پایین‌تر می‌آید
+    pub quality: u8,
+}
+
+/// زمان‌های انتظار درخواست‌های HTTP به پنل
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+}
+
+/// نگهداری موقت محصولات حذف‌شده برای بازگردانی
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrashConfig {
+    /// چند ثانیه بعد از حذف، اطلاعات محصول برای بازگردانی نگه داشته شود
+    pub ttl_secs: u64,
+}
+
+/// کش دسته‌بندی‌های هر چت
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CategoriesConfig {
+    /// چند ثانیه دسته‌بندی‌های خوانده‌شده تازه حساب شوند
+    pub cache_ttl_secs: u64,
+}
+
+/// محل نگهداری اطلاعات ورود (آدرس پنل و توکن) هر چت
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// مسیر فایل SQLite (فقط برای `sqlite`)
+    pub database_path: PathBuf,
+    /// کلید base64 (۳۲ بایت) برای رمز کردن Api-Keyها؛ اگر خالی باشد توکن‌ها رمز نمی‌شوند
+    pub encryption_key: Option<String>,
+    /// کلیدهای قبلی که فقط برای رمزگشایی استفاده می‌شوند (دوران کلید)
+    pub previous_encryption_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// فقط در حافظه؛ با ری‌استارت پاک می‌شود
+    Memory,
+    /// فایل SQLite روی دیسک
+    Sqlite,
+}
+
+/// محل نگهداری وضعیت مکالمه‌ها (مرحلهٔ فعلی هر کاربر)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DialogueConfig {
+    pub backend: DialogueBackend,
+    /// آدرس Redis (فقط برای `redis`)، مثل `redis://127.0.0.1:6379`
+    pub redis_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DialogueBackend {
+    /// فقط در حافظه؛ با ری‌استارت پاک می‌شود
+    Memory,
+    /// همان فایل `storage.database_path`
+    Sqlite,
+    /// سرور Redis (یا سازگار)
+    Redis,
+}
+
+impl std::str::FromStr for DialogueBackend {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(DialogueBackend::Memory),
+            "sqlite" => Ok(DialogueBackend::Sqlite),
+            "redis" => Ok(DialogueBackend::Redis),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::str::FromStr for StorageBackend {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "memory" => Ok(StorageBackend::Memory),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            bot_token: String::new(),
+            default_panel_url: None,
+            log_level: "info".to_string(),
+            images: ImageConfig::default(),
+            http: HttpConfig::default(),
+            storage: StorageConfig::default(),
+            dialogue: DialogueConfig::default(),
+            trash: TrashConfig::default(),
+            categories: CategoriesConfig::default(),
+        }
+    }
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        Self {
+            allowed_extensions: ["jpg", "jpeg", "png", "gif", "webp"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            max_size_bytes: 2 * 1024 * 1024,
+            max_input_bytes: TELEGRAM_DOWNLOAD_LIMIT,
+            max_dimension: 1600,
+            quality: 85,
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Sqlite,
+            database_path: PathBuf::from("torob_bot.sqlite3"),
+            encryption_key: None,
+            previous_encryption_keys: Vec::new(),
+        }
+    }
+}
+
+impl Default for DialogueConfig {
+    fn default() -> Self {
+        Self {
+            backend: DialogueBackend::Sqlite,
+            redis_url: None,
+        }
+    }
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { ttl_secs: 15 * 60 }
+    }
+}
+
+impl Default for CategoriesConfig {
+    fn default() -> Self {
+        Self { cache_ttl_secs: 5 * 60 }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            connect_timeout_secs: 10,
+        }
+    }
+}
+
+/// خطاهای بارگذاری یا اعتبارسنجی تنظیمات
+#[derive(Debug)]
+pub enum ConfigError {
+    /// فایل تنظیمات خوانده نشد
+    Io { path: PathBuf, source: std::io::Error },
+    /// فایل تنظیمات TOML معتبر نیست
+    Parse { path: PathBuf, message: String },
+    /// مقدار متغیر محیطی قابل تبدیل نیست
+    InvalidEnv { key: &'static str, value: String },
+    /// یک یا چند مقدار نامعتبر است
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => {
+                write!(f, "cannot read config file {}: {}", path.display(), source)
+            }
+            ConfigError::Parse { path, message } => {
+                write!(f, "invalid config file {}: {}", path.display(), message)
+            }
+            ConfigError::InvalidEnv { key, value } => {
+                write!(f, "invalid value for environment variable {}: {:?}", key, value)
+            }
+            ConfigError::Invalid(problems) => {
+                write!(f, "invalid configuration:")?;
+                for p in problems {
+                    write!(f, "\n  - {}", p)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl AppConfig {
+    /// تنظیمات را از فایل (در صورت وجود) و سپس متغیرهای محیطی می‌خواند و اعتبارسنجی می‌کند.
+    ///
+    /// اگر `TOROB_BOT_CONFIG` ست شده باشد فایل باید وجود داشته باشد؛
+    /// در غیر این صورت `config.toml` اختیاری است.
+    pub fn load() -> Result<Self, ConfigError> {
+        let (path, required) = match std::env::var(CONFIG_PATH_ENV) {
+            Ok(p) if !p.trim().is_empty() => (PathBuf::from(p.trim()), true),
+            _ => (PathBuf::from(DEFAULT_CONFIG_PATH), false),
+        };
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(s) => Some(s),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !required => None,
+            Err(source) => return Err(ConfigError::Io { path, source }),
+        };
+
+        Self::from_sources(&path, contents.as_deref(), |key| std::env::var(key).ok())
+    }
+
+    /// ساخت تنظیمات از متن فایل و یک تابع خواندن env (برای تست‌پذیری جدا شده است)
+    pub fn from_sources(
+        path: &Path,
+        contents: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let mut cfg: AppConfig = match contents {
+            Some(text) => toml::from_str(text).map_err(|e| ConfigError::Parse {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?,
+            None => AppConfig::default(),
+        };
+
+        cfg.apply_env(env)?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// مقادیر متغیرهای محیطی روی مقادیر فایل نوشته می‌شوند
+    fn apply_env(&mut self, env: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        if let Some(v) = env("TOROB_BOT_TOKEN") {
+            self.bot_token = v;
+        }
+        if let Some(v) = env("TOROB_DEFAULT_PANEL_URL") {
+            self.default_panel_url = if v.trim().is_empty() { None } else { Some(v) };
+        }
+        if let Some(v) = env("TOROB_LOG_LEVEL") {
+            self.log_level = v;
+        }
+        if let Some(v) = env("TOROB_IMAGE_ALLOWED_EXTENSIONS") {
+            self.images.allowed_extensions = v
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        if let Some(v) = env("TOROB_IMAGE_MAX_SIZE_BYTES") {
+            self.images.max_size_bytes = parse_env("TOROB_IMAGE_MAX_SIZE_BYTES", v)?;
+        }
+        if let Some(v) = env("TOROB_IMAGE_MAX_INPUT_BYTES") {
+            self.images.max_input_bytes = parse_env("TOROB_IMAGE_MAX_INPUT_BYTES", v)?;
+        }
+        if let Some(v) = env("TOROB_IMAGE_MAX_DIMENSION") {
+            self.images.max_dimension = parse_env("TOROB_IMAGE_MAX_DIMENSION", v)?;
+        }
+        if let Some(v) = env("TOROB_IMAGE_QUALITY") {
+            self.images.quality = parse_env("TOROB_IMAGE_QUALITY", v)?;
+        }
+        if let Some(v) = env("TOROB_HTTP_TIMEOUT_SECS") {
+            self.http.timeout_secs = parse_env("TOROB_HTTP_TIMEOUT_SECS", v)?;
+        }
+        if let Some(v) = env("TOROB_HTTP_CONNECT_TIMEOUT_SECS") {
+            self.http.connect_timeout_secs = parse_env("TOROB_HTTP_CONNECT_TIMEOUT_SECS", v)?;
+        }
+        if let Some(v) = env("TOROB_STORAGE_BACKEND") {
+            self.storage.backend = parse_env("TOROB_STORAGE_BACKEND", v)?;
+        }
+        if let Some(v) = env("TOROB_DATABASE_PATH") {
+            self.storage.database_path = PathBuf::from(v.trim());
+        }
+        if let Some(v) = env("TOROB_DIALOGUE_BACKEND") {
+            self.dialogue.backend = parse_env("TOROB_DIALOGUE_BACKEND", v)?;
+        }
+        if let Some(v) = env("TOROB_REDIS_URL") {
+            self.dialogue.redis_url = if v.trim().is_empty() { None } else { Some(v) };
+        }
+        if let Some(v) = env("TOROB_TRASH_TTL_SECS") {
+            self.trash.ttl_secs = parse_env("TOROB_TRASH_TTL_SECS", v)?;
+        }
+        if let Some(v) = env("TOROB_CATEGORY_CACHE_TTL_SECS") {
+            self.categories.cache_ttl_secs = parse_env("TOROB_CATEGORY_CACHE_TTL_SECS", v)?;
+        }
+        if let Some(v) = env("TOROB_ENCRYPTION_KEY") {
+            self.storage.encryption_key = if v.trim().is_empty() { None } else { Some(v) };
+        }
+        if let Some(v) = env("TOROB_PREVIOUS_ENCRYPTION_KEYS") {
+            self.storage.previous_encryption_keys = v
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// بررسی مقادیر؛ همهٔ مشکلات با هم گزارش می‌شوند
+    fn validate(&mut self) -> Result<(), ConfigError> {
+        let mut problems: Vec<String> = Vec::new();
+
+        self.bot_token = self.bot_token.trim().to_string();
+        if self.bot_token.is_empty() {
+            problems.push("bot_token is required (set it in the config file or TOROB_BOT_TOKEN)".into());
+        } else if !looks_like_bot_token(&self.bot_token) {
+            problems.push("bot_token must look like `<bot id>:<secret>` as issued by @BotFather".into());
+        }
+
+        if let Some(url) = &self.default_panel_url {
+            let url = url.trim().trim_end_matches('/').to_string();
+            if !url.starts_with("https://") || reqwest::Url::parse(&url).is_err() {
+                problems.push(format!("default_panel_url must be a valid https URL, got {:?}", url));
+            }
+            self.default_panel_url = Some(url);
+        }
+
+        self.log_level = self.log_level.trim().to_ascii_lowercase();
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            problems.push(format!(
+                "log_level must be one of {:?}, got {:?}",
+                LOG_LEVELS, self.log_level
+            ));
+        }
+
+        self.images.allowed_extensions = self
+            .images
+            .allowed_extensions
+            .iter()
+            .map(|e| e.trim().trim_start_matches('.').to_ascii_lowercase())
+            .collect();
+        if self.images.allowed_extensions.is_empty()
+            || self.images.allowed_extensions.iter().any(|e| e.is_empty())
+        {
+            problems.push("images.allowed_extensions must list at least one extension".into());
+        }
+
+        if self.images.max_size_bytes == 0 || self.images.max_size_bytes > TELEGRAM_DOWNLOAD_LIMIT {
+            problems.push(format!(
+                "images.max_size_bytes must be between 1 and {} (Telegram download limit)",
+                TELEGRAM_DOWNLOAD_LIMIT
+            ));
+        }
+
+        if self.images.max_input_bytes == 0 || self.images.max_input_bytes > TELEGRAM_DOWNLOAD_LIMIT {
+            problems.push(format!(
+                "images.max_input_bytes must be between 1 and {} (Telegram download limit)",
+                TELEGRAM_DOWNLOAD_LIMIT
+            ));
+        }
+        if self.images.max_dimension < 64 {
+            problems.push("images.max_dimension must be at least 64".into());
+        }
+        if !(1..=100).contains(&self.images.quality) {
+            problems.push("images.quality must be between 1 and 100".into());
+        }
+
+        if self.http.timeout_secs == 0 {
+            problems.push("http.timeout_secs must be greater than zero".into());
+        }
+        if self.http.connect_timeout_secs == 0 {
+            problems.push("http.connect_timeout_secs must be greater than zero".into());
+        }
+
+        if self.trash.ttl_secs == 0 {
+            problems.push("trash.ttl_secs must be greater than zero".into());
+        }
+        if self.categories.cache_ttl_secs == 0 {
+            problems.push("categories.cache_ttl_secs must be greater than zero".into());
+        }
+
+        if self.uses_database() && self.storage.database_path.as_os_str().is_empty() {
+            problems.push("storage.database_path is required for the sqlite backends".into());
+        }
+
+        if self.dialogue.backend == DialogueBackend::Redis {
+            match self.dialogue.redis_url.as_deref().map(str::trim) {
+                Some(url) if url.starts_with("redis://") || url.starts_with("rediss://") => {}
+                _ => problems.push(
+                    "dialogue.redis_url must be a redis:// or rediss:// URL for the redis backend"
+                        .into(),
+                ),
+            }
+        }
+
+        if let Some(key) = &self.storage.encryption_key
+            && let Err(e) = parse_key(key)
+        {
+            problems.push(format!("storage.encryption_key: {}", e));
+        }
+        for (i, key) in self.storage.previous_encryption_keys.iter().enumerate() {
+            if let Err(e) = parse_key(key) {
+                problems.push(format!("storage.previous_encryption_keys[{}]: {}", i, e));
+            }
+        }
+        if self.storage.encryption_key.is_none() && !self.storage.previous_encryption_keys.is_empty() {
+            problems.push("storage.previous_encryption_keys requires storage.encryption_key".into());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+
+    /// آیا فایل SQLite لازم است؟ (برای اعتبارنامه‌ها یا وضعیت مکالمه‌ها)
+    pub fn uses_database(&self) -> bool {
+        self.storage.backend == StorageBackend::Sqlite
+            || self.dialogue.backend == DialogueBackend::Sqlite
+    }
+
+    /// رمزنگار توکن‌ها بر اساس کلیدهای تنظیم‌شده (کلیدها قبلاً در `validate` بررسی شده‌اند)
+    pub fn token_cipher(&self) -> Option<TokenCipher> {
+        let key = self.storage.encryption_key.as_ref()?;
+        TokenCipher::new(key, &self.storage.previous_encryption_keys).ok()
+    }
+
+    /// زمان انتظار کل درخواست
+    pub fn http_timeout(&self) -> Duration {
+        Duration::from_secs(self.http.timeout_secs)
+    }
+
+    /// مدت نگهداری محصولات حذف‌شده برای بازگردانی
+    pub fn trash_ttl(&self) -> Duration {
+        Duration::from_secs(self.trash.ttl_secs)
+    }
+
+    /// مدت تازه ماندن کش دسته‌بندی‌ها
+    pub fn category_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.categories.cache_ttl_secs)
+    }
+
+    /// زمان انتظار برقراری اتصال
+    pub fn http_connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.http.connect_timeout_secs)
+    }
+}
+
+fn parse_env<T: std::str::FromStr>(key: &'static str, value: String) -> Result<T, ConfigError> {
+    value
+        .trim()
+        .parse::<T>()
+        .map_err(|_| ConfigError::InvalidEnv { key, value })
+}
+
+fn looks_like_bot_token(token: &str) -> bool {
+    match token.split_once(':') {
+        Some((id, secret)) => {
+            !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) && secret.len() >= 20
+        }
+        None => false,
+    }
+}
+
+/// تنظیمات سراسری؛ یک‌بار در `main` مقداردهی می‌شود
+static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+
+/// ثبت تنظیمات سراسری (فقط بار اول اثر دارد)
+pub fn init_config(cfg: AppConfig) -> &'static AppConfig {
+    CONFIG.get_or_init(|| cfg)
+}
+
+/// خواندن تنظیمات سراسری؛ اگر مقداردهی نشده باشد مقادیر پیش‌فرض برگردانده می‌شود
+pub fn config() -> &'static AppConfig {
+    CONFIG.get_or_init(AppConfig::default)
+}
+
+/// ساخت کلاینت HTTP با زمان‌های انتظار تنظیم‌شده
+pub fn http_client_builder() -> reqwest::ClientBuilder {
+    let cfg = config();
+    reqwest::Client::builder()
+        .timeout(cfg.http_timeout())
+        .connect_timeout(cfg.http_connect_timeout())
+}
+
+#[cfg(test)]
+mod test_config {
+    use super::*;
+    use std::collections::HashMap;
+
+    const TOKEN: &str = "123456:ABCdefGHIjklMNOpqrSTUvwx";
+
+    fn load(contents: Option<&str>, env: &[(&str, &str)]) -> Result<AppConfig, ConfigError> {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        AppConfig::from_sources(Path::new("test.toml"), contents, |k| env.get(k).cloned())
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let file = format!(
+            "bot_token = \"{}\"\nlog_level = \"debug\"\n[images]\nmax_size_bytes = 1000\n",
+            TOKEN
+        );
+        let cfg = load(
+            Some(&file),
+            &[
+                ("TOROB_IMAGE_MAX_SIZE_BYTES", "4096"),
+                ("TOROB_IMAGE_ALLOWED_EXTENSIONS", "PNG, .jpg"),
+                ("TOROB_IMAGE_QUALITY", "70"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(cfg.bot_token, TOKEN);
+        assert_eq!(cfg.log_level, "debug");
+        assert_eq!(cfg.images.max_size_bytes, 4096);
+        assert_eq!(cfg.images.allowed_extensions, vec!["png", "jpg"]);
+        assert_eq!(cfg.images.quality, 70);
+        assert_eq!(cfg.images.max_dimension, 1600);
+        assert_eq!(cfg.http.timeout_secs, 30);
+    }
+
+    #[test]
+    fn test_missing_token_and_bad_values_are_reported() {
+        let err = load(
+            Some("default_panel_url = \"http://shop.ir\"\n[http]\ntimeout_secs = 0\n"),
+            &[],
+        )
+        .unwrap_err();
+
+        match err {
+            ConfigError::Invalid(problems) => assert_eq!(problems.len(), 3),
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_env_number() {
+        let err = load(None, &[("TOROB_BOT_TOKEN", TOKEN), ("TOROB_HTTP_TIMEOUT_SECS", "abc")])
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidEnv { key: "TOROB_HTTP_TIMEOUT_SECS", .. }));
+    }
+}
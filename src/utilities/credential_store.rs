@@ -0,0 +1,428 @@
+use crate::utilities::crypto::{CryptoError, TokenCipher, is_encrypted, stored_key_id};
+use crate::utilities::database::SharedConnection;
+use rusqlite::{OptionalExtension, params};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+/// اطلاعات ورود هر چت به پنل فروشگاه
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserInfo {
+    /// آدرس پنل (بدون `/` انتهایی)
+    pub site: Option<String>,
+    /// Api-Key مدیریت
+    pub token: Option<String>,
+}
+
+/// خطای لایهٔ ذخیره‌سازی اعتبارنامه‌ها
+#[derive(Debug)]
+pub enum StoreError {
+    Database(rusqlite::Error),
+    LockPoisoned,
+    /// رمزگشایی/بررسی کلید ناموفق بود
+    Crypto { chat_id: String, source: CryptoError },
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Database(e) => write!(f, "credential store database error: {}", e),
+            StoreError::LockPoisoned => write!(f, "credential store lock poisoned"),
+            StoreError::Crypto { chat_id, source } => {
+                write!(f, "token of chat {}: {}", chat_id, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StoreError::Database(e) => Some(e),
+            StoreError::LockPoisoned => None,
+            StoreError::Crypto { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        StoreError::Database(e)
+    }
+}
+
+/// محل نگهداری آدرس پنل و توکن هر چت؛ پیاده‌سازی‌ها قابل تعویض‌اند
+pub trait CredentialStore: Send + Sync {
+    fn get(&self, chat_id: &str) -> Result<Option<UserInfo>, StoreError>;
+    fn set_site(&self, chat_id: &str, site: &str) -> Result<(), StoreError>;
+    fn set_token(&self, chat_id: &str, token: &str) -> Result<(), StoreError>;
+    fn remove_site(&self, chat_id: &str) -> Result<(), StoreError>;
+    fn remove_token(&self, chat_id: &str) -> Result<(), StoreError>;
+    fn list(&self) -> Result<Vec<(String, UserInfo)>, StoreError>;
+}
+
+/// ====== پیاده‌سازی درون حافظه (با ری‌استارت پاک می‌شود) ======
+#[derive(Default)]
+pub struct MemoryCredentialStore {
+    map: RwLock<HashMap<String, UserInfo>>,
+}
+
+impl MemoryCredentialStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn update(&self, chat_id: &str, f: impl FnOnce(&mut UserInfo)) -> Result<(), StoreError> {
+        let mut w = self.map.write().map_err(|_| StoreError::LockPoisoned)?;
+        let entry = w.entry(chat_id.to_string()).or_default();
+        f(entry);
+        if entry.site.is_none() && entry.token.is_none() {
+            w.remove(chat_id);
+        }
+        Ok(())
+    }
+}
+
+impl CredentialStore for MemoryCredentialStore {
+    fn get(&self, chat_id: &str) -> Result<Option<UserInfo>, StoreError> {
+        let r = self.map.read().map_err(|_| StoreError::LockPoisoned)?;
+        Ok(r.get(chat_id).cloned())
+    }
+
+    fn set_site(&self, chat_id: &str, site: &str) -> Result<(), StoreError> {
+        self.update(chat_id, |u| u.site = Some(site.to_string()))
+    }
+
+    fn set_token(&self, chat_id: &str, token: &str) -> Result<(), StoreError> {
+        self.update(chat_id, |u| u.token = Some(token.to_string()))
+    }
+
+    fn remove_site(&self, chat_id: &str) -> Result<(), StoreError> {
+        self.update(chat_id, |u| u.site = None)
+    }
+
+    fn remove_token(&self, chat_id: &str) -> Result<(), StoreError> {
+        self.update(chat_id, |u| u.token = None)
+    }
+
+    fn list(&self) -> Result<Vec<(String, UserInfo)>, StoreError> {
+        let r = self.map.read().map_err(|_| StoreError::LockPoisoned)?;
+        Ok(r.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    }
+}
+
+/// ====== پیاده‌سازی SQLite (جدول `user_info`) ======
+pub struct SqliteCredentialStore {
+    conn: SharedConnection,
+}
+
+impl SqliteCredentialStore {
+    pub fn new(conn: SharedConnection) -> Self {
+        Self { conn }
+    }
+
+    /// اجرای یک دستور نوشتن؛ ردیف‌هایی که هر دو ستونشان خالی شده حذف می‌شوند
+    fn write(&self, sql: &str, chat_id: &str, value: Option<&str>) -> Result<(), StoreError> {
+        let conn = self.conn.lock().map_err(|_| StoreError::LockPoisoned)?;
+        conn.execute(sql, params![chat_id, value])?;
+        conn.execute(
+            "DELETE FROM user_info WHERE chat_id = ?1 AND site IS NULL AND token IS NULL",
+            params![chat_id],
+        )?;
+        Ok(())
+    }
+}
+
+impl CredentialStore for SqliteCredentialStore {
+    fn get(&self, chat_id: &str) -> Result<Option<UserInfo>, StoreError> {
+        let conn = self.conn.lock().map_err(|_| StoreError::LockPoisoned)?;
+        let info = conn
+            .query_row(
+                "SELECT site, token FROM user_info WHERE chat_id = ?1",
+                params![chat_id],
+                |row| {
+                    Ok(UserInfo {
+                        site: row.get(0)?,
+                        token: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(info)
+    }
+
+    fn set_site(&self, chat_id: &str, site: &str) -> Result<(), StoreError> {
+        self.write(
+            "INSERT INTO user_info (chat_id, site) VALUES (?1, ?2)
+             ON CONFLICT(chat_id) DO UPDATE SET site = excluded.site,
+                                                updated_at = strftime('%s', 'now')",
+            chat_id,
+            Some(site),
+        )
+    }
+
+    fn set_token(&self, chat_id: &str, token: &str) -> Result<(), StoreError> {
+        self.write(
+            "INSERT INTO user_info (chat_id, token) VALUES (?1, ?2)
+             ON CONFLICT(chat_id) DO UPDATE SET token = excluded.token,
+                                                updated_at = strftime('%s', 'now')",
+            chat_id,
+            Some(token),
+        )
+    }
+
+    fn remove_site(&self, chat_id: &str) -> Result<(), StoreError> {
+        self.write(
+            "UPDATE user_info SET site = ?2, updated_at = strftime('%s', 'now') WHERE chat_id = ?1",
+            chat_id,
+            None,
+        )
+    }
+
+    fn remove_token(&self, chat_id: &str) -> Result<(), StoreError> {
+        self.write(
+            "UPDATE user_info SET token = ?2, updated_at = strftime('%s', 'now') WHERE chat_id = ?1",
+            chat_id,
+            None,
+        )
+    }
+
+    fn list(&self) -> Result<Vec<(String, UserInfo)>, StoreError> {
+        let conn = self.conn.lock().map_err(|_| StoreError::LockPoisoned)?;
+        let mut stmt = conn.prepare("SELECT chat_id, site, token FROM user_info ORDER BY chat_id")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                UserInfo {
+                    site: row.get(1)?,
+                    token: row.get(2)?,
+                },
+            ))
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+/// ====== لایهٔ رمزنگاری روی هر مخزن دیگر ======
+///
+/// توکن‌ها قبل از نوشتن رمز و بعد از خواندن رمزگشایی می‌شوند؛ آدرس پنل رمز نمی‌شود.
+/// اگر کلیدی تنظیم نشده باشد توکن‌ها به صورت متن ساده ذخیره می‌شوند.
+pub struct EncryptedCredentialStore {
+    inner: Box<dyn CredentialStore>,
+    cipher: Option<TokenCipher>,
+}
+
+impl EncryptedCredentialStore {
+    /// ساخت مخزن و بررسی اینکه همهٔ توکن‌های موجود با کلیدهای شناخته‌شده رمز شده‌اند.
+    ///
+    /// اگر حتی یک توکن با کلید ناشناخته رمز شده باشد خطا برمی‌گرداند تا ربات بالا نیاید.
+    pub fn open(
+        inner: Box<dyn CredentialStore>,
+        cipher: Option<TokenCipher>,
+    ) -> Result<Self, StoreError> {
+        let store = Self { inner, cipher };
+        let mut plaintext = 0usize;
+
+        for (chat_id, info) in store.inner.list()? {
+            let Some(token) = info.token else { continue };
+            match (stored_key_id(&token), &store.cipher) {
+                (None, _) => plaintext += 1,
+                (Some(_), None) => {
+                    return Err(StoreError::Crypto {
+                        chat_id,
+                        source: CryptoError::NoKeyConfigured,
+                    });
+                }
+                (Some(id), Some(cipher)) if !cipher.knows_key(id) => {
+                    return Err(StoreError::Crypto {
+                        chat_id,
+                        source: CryptoError::UnknownKey(id.to_string()),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if plaintext > 0 && store.cipher.is_some() {
+            eprintln!(
+                "{} token(s) are stored unencrypted; run `reencrypt-tokens` to encrypt them",
+                plaintext
+            );
+        }
+
+        Ok(store)
+    }
+
+    /// رمز کردن دوبارهٔ همهٔ توکن‌ها با کلید فعلی (بعد از دوران کلید یا برای توکن‌های قدیمی)
+    ///
+    /// تعداد توکن‌های بازنویسی‌شده را برمی‌گرداند (بدون کلید کاری انجام نمی‌شود).
+    pub fn reencrypt_all(&self) -> Result<usize, StoreError> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(0);
+        };
+
+        let mut count = 0usize;
+        for (chat_id, info) in self.inner.list()? {
+            let Some(stored) = info.token else { continue };
+            if stored_key_id(&stored) == Some(cipher.current_key_id()) {
+                continue;
+            }
+            let plain = self.decrypt(&chat_id, stored)?;
+            self.inner.set_token(&chat_id, &cipher.encrypt(&chat_id, &plain))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn decrypt(&self, chat_id: &str, stored: String) -> Result<String, StoreError> {
+        let crypto_err = |source| StoreError::Crypto {
+            chat_id: chat_id.to_string(),
+            source,
+        };
+        match &self.cipher {
+            Some(cipher) => cipher.decrypt(chat_id, &stored).map_err(crypto_err),
+            None if is_encrypted(&stored) => Err(crypto_err(CryptoError::NoKeyConfigured)),
+            None => Ok(stored),
+        }
+    }
+
+    fn decrypt_info(&self, chat_id: &str, info: UserInfo) -> Result<UserInfo, StoreError> {
+        Ok(UserInfo {
+            site: info.site,
+            token: info.token.map(|t| self.decrypt(chat_id, t)).transpose()?,
+        })
+    }
+}
+
+impl CredentialStore for EncryptedCredentialStore {
+    fn get(&self, chat_id: &str) -> Result<Option<UserInfo>, StoreError> {
+        self.inner
+            .get(chat_id)?
+            .map(|info| self.decrypt_info(chat_id, info))
+            .transpose()
+    }
+
+    fn set_site(&self, chat_id: &str, site: &str) -> Result<(), StoreError> {
+        self.inner.set_site(chat_id, site)
+    }
+
+    fn set_token(&self, chat_id: &str, token: &str) -> Result<(), StoreError> {
+        match &self.cipher {
+            Some(cipher) => self.inner.set_token(chat_id, &cipher.encrypt(chat_id, token)),
+            None => self.inner.set_token(chat_id, token),
+        }
+    }
+
+    fn remove_site(&self, chat_id: &str) -> Result<(), StoreError> {
+        self.inner.remove_site(chat_id)
+    }
+
+    fn remove_token(&self, chat_id: &str) -> Result<(), StoreError> {
+        self.inner.remove_token(chat_id)
+    }
+
+    fn list(&self) -> Result<Vec<(String, UserInfo)>, StoreError> {
+        self.inner
+            .list()?
+            .into_iter()
+            .map(|(chat_id, info)| {
+                let info = self.decrypt_info(&chat_id, info)?;
+                Ok((chat_id, info))
+            })
+            .collect()
+    }
+}
+
+/// مخزن سراسری؛ یک‌بار در `main` مقداردهی می‌شود
+static STORE: OnceLock<Box<dyn CredentialStore>> = OnceLock::new();
+
+/// ثبت مخزن سراسری (فقط بار اول اثر دارد)
+pub fn init_credential_store(store: Box<dyn CredentialStore>) -> &'static dyn CredentialStore {
+    STORE.get_or_init(|| store).as_ref()
+}
+
+/// دسترسی به مخزن؛ اگر مقداردهی نشده باشد از مخزن درون حافظه استفاده می‌شود
+pub fn credential_store() -> &'static dyn CredentialStore {
+    STORE
+        .get_or_init(|| Box::new(MemoryCredentialStore::new()))
+        .as_ref()
+}
+
+/// اطلاعات کامل ورود یک چت
+pub fn get_user_info<S: AsRef<str>>(chat_id: S) -> Option<UserInfo> {
+    match credential_store().get(chat_id.as_ref()) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("cannot read credentials of {}: {}", chat_id.as_ref(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_credential_store {
+    use super::*;
+    use crate::utilities::database::open_in_memory;
+
+    fn exercise(store: &dyn CredentialStore) {
+        store.set_site("1", "https://shop.ir").unwrap();
+        store.set_token("1", "key-1").unwrap();
+        store.set_token("1", "key-2").unwrap();
+        store.set_site("2", "https://other.ir").unwrap();
+
+        assert_eq!(
+            store.get("1").unwrap(),
+            Some(UserInfo {
+                site: Some("https://shop.ir".into()),
+                token: Some("key-2".into()),
+            })
+        );
+
+        store.remove_token("1").unwrap();
+        assert_eq!(store.get("1").unwrap().unwrap().token, None);
+
+        store.remove_site("2").unwrap();
+        assert_eq!(store.get("2").unwrap(), None);
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_memory_store() {
+        exercise(&MemoryCredentialStore::new());
+    }
+
+    #[test]
+    fn test_sqlite_store() {
+        exercise(&SqliteCredentialStore::new(open_in_memory().unwrap()));
+    }
+
+    #[test]
+    fn test_encrypted_store_rotation() {
+        use crate::utilities::crypto::generate_key;
+
+        let conn = open_in_memory().unwrap();
+        let raw = || Box::new(SqliteCredentialStore::new(conn.clone()));
+        let (old_key, new_key) = (generate_key(), generate_key());
+
+        let store =
+            EncryptedCredentialStore::open(raw(), Some(TokenCipher::new(&old_key, &[]).unwrap()))
+                .unwrap();
+        exercise(&store);
+        store.set_token("3", "api-key").unwrap();
+        assert!(is_encrypted(&raw().get("3").unwrap().unwrap().token.unwrap()));
+
+        // کلید ناشناخته یا نبودن کلید: شروع ممنوع
+        let unknown = TokenCipher::new(&new_key, &[]).unwrap();
+        assert!(EncryptedCredentialStore::open(raw(), Some(unknown)).is_err());
+        assert!(EncryptedCredentialStore::open(raw(), None).is_err());
+
+        let rotated = TokenCipher::new(&new_key, std::slice::from_ref(&old_key)).unwrap();
+        let store = EncryptedCredentialStore::open(raw(), Some(rotated)).unwrap();
+        assert_eq!(store.reencrypt_all().unwrap(), 1);
+        assert_eq!(store.get("3").unwrap().unwrap().token.as_deref(), Some("api-key"));
+
+        // حالا کلید قدیمی قابل حذف است
+        let only_new = TokenCipher::new(&new_key, &[]).unwrap();
+        assert!(EncryptedCredentialStore::open(raw(), Some(only_new)).is_ok());
+    }
+}
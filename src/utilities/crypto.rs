@@ -0,0 +1,200 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// پیشوند مقادیر رمزشده در پایگاه داده: `enc:v1:<key id>:<base64(nonce || ciphertext)>`
+const PREFIX: &str = "enc:v1:";
+
+/// طول nonce در XChaCha20-Poly1305
+const NONCE_LEN: usize = 24;
+
+/// خطاهای رمزنگاری توکن‌ها
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    /// کلید base64 معتبر ۳۲ بایتی نیست
+    InvalidKey(String),
+    /// مقدار با کلیدی رمز شده که در تنظیمات وجود ندارد
+    UnknownKey(String),
+    /// مقدار رمزشده خراب است یا با chat_id دیگری رمز شده
+    Corrupted,
+    /// مقدار رمزشده است ولی هیچ کلیدی تنظیم نشده
+    NoKeyConfigured,
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::InvalidKey(why) => write!(f, "invalid encryption key: {}", why),
+            CryptoError::UnknownKey(id) => write!(f, "value is encrypted with unknown key {}", id),
+            CryptoError::Corrupted => write!(f, "encrypted value is corrupted or tampered"),
+            CryptoError::NoKeyConfigured => {
+                write!(f, "value is encrypted but no encryption key is configured")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+/// خواندن کلید base64 (دقیقاً ۳۲ بایت)
+pub fn parse_key(encoded: &str) -> Result<[u8; 32], CryptoError> {
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| CryptoError::InvalidKey(e.to_string()))?;
+    bytes
+        .try_into()
+        .map_err(|b: Vec<u8>| CryptoError::InvalidKey(format!("expected 32 bytes, got {}", b.len())))
+}
+
+/// ساخت یک کلید تصادفی جدید به صورت base64
+pub fn generate_key() -> String {
+    BASE64.encode(XChaCha20Poly1305::generate_key(&mut OsRng))
+}
+
+/// شناسهٔ کوتاه کلید (۸ کاراکتر hex از SHA-256)؛ خودِ کلید را لو نمی‌دهد
+pub fn key_id(key: &[u8; 32]) -> String {
+    Sha256::digest(key)[..4]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// آیا مقدار ذخیره‌شده رمز شده است؟
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(PREFIX)
+}
+
+/// شناسهٔ کلیدی که مقدار با آن رمز شده (برای مقادیر رمزنشده None)
+pub fn stored_key_id(stored: &str) -> Option<&str> {
+    stored.strip_prefix(PREFIX)?.split(':').next()
+}
+
+/// رمزنگاری احراز‌هویت‌شده (XChaCha20-Poly1305) برای Api-Keyها.
+///
+/// شناسهٔ چت به عنوان associated data استفاده می‌شود تا مقدار رمزشدهٔ یک چت
+/// برای چت دیگر قابل استفاده نباشد.
+pub struct TokenCipher {
+    current_id: String,
+    current: XChaCha20Poly1305,
+    /// کلیدهای قبلی فقط برای رمزگشایی (دوران کلید)
+    previous: Vec<(String, XChaCha20Poly1305)>,
+}
+
+impl TokenCipher {
+    /// `current` برای رمزنگاری و رمزگشایی؛ `previous` فقط برای رمزگشایی
+    pub fn new(current: &str, previous: &[String]) -> Result<Self, CryptoError> {
+        let key = parse_key(current)?;
+        let mut old = Vec::with_capacity(previous.len());
+        for p in previous {
+            let k = parse_key(p)?;
+            old.push((key_id(&k), XChaCha20Poly1305::new(Key::from_slice(&k))));
+        }
+        Ok(Self {
+            current_id: key_id(&key),
+            current: XChaCha20Poly1305::new(Key::from_slice(&key)),
+            previous: old,
+        })
+    }
+
+    /// شناسهٔ کلید فعلی
+    pub fn current_key_id(&self) -> &str {
+        &self.current_id
+    }
+
+    /// آیا این شناسهٔ کلید در تنظیمات وجود دارد؟
+    pub fn knows_key(&self, id: &str) -> bool {
+        self.cipher_for(id).is_some()
+    }
+
+    fn cipher_for(&self, id: &str) -> Option<&XChaCha20Poly1305> {
+        if id == self.current_id {
+            return Some(&self.current);
+        }
+        self.previous.iter().find(|(k, _)| k == id).map(|(_, c)| c)
+    }
+
+    /// رمز کردن با کلید فعلی
+    pub fn encrypt(&self, chat_id: &str, plaintext: &str) -> String {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .current
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext.as_bytes(),
+                    aad: chat_id.as_bytes(),
+                },
+            )
+            .expect("XChaCha20-Poly1305 encryption cannot fail for in-memory buffers");
+
+        let mut blob = nonce.to_vec();
+        blob.extend_from_slice(&ciphertext);
+        format!("{}{}:{}", PREFIX, self.current_id, BASE64.encode(blob))
+    }
+
+    /// رمزگشایی؛ مقادیر رمزنشدهٔ قدیمی همان‌طور برگردانده می‌شوند
+    pub fn decrypt(&self, chat_id: &str, stored: &str) -> Result<String, CryptoError> {
+        let Some(rest) = stored.strip_prefix(PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let (id, payload) = rest.split_once(':').ok_or(CryptoError::Corrupted)?;
+        let cipher = self
+            .cipher_for(id)
+            .ok_or_else(|| CryptoError::UnknownKey(id.to_string()))?;
+
+        let blob = BASE64.decode(payload).map_err(|_| CryptoError::Corrupted)?;
+        if blob.len() <= NONCE_LEN {
+            return Err(CryptoError::Corrupted);
+        }
+        let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+        let plain = cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: chat_id.as_bytes(),
+                },
+            )
+            .map_err(|_| CryptoError::Corrupted)?;
+        String::from_utf8(plain).map_err(|_| CryptoError::Corrupted)
+    }
+}
+
+#[cfg(test)]
+mod test_crypto {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_rotation() {
+        let old_key = generate_key();
+        let new_key = generate_key();
+
+        let old = TokenCipher::new(&old_key, &[]).unwrap();
+        let stored = old.encrypt("42", "secret-api-key");
+        assert!(is_encrypted(&stored));
+        assert!(!stored.contains("secret-api-key"));
+        assert_eq!(stored_key_id(&stored), Some(old.current_key_id()));
+
+        // بعد از دوران، کلید قبلی فقط برای خواندن در دسترس است
+        let rotated = TokenCipher::new(&new_key, &[old_key]).unwrap();
+        assert_eq!(rotated.decrypt("42", &stored).unwrap(), "secret-api-key");
+        let re = rotated.encrypt("42", "secret-api-key");
+        assert_eq!(stored_key_id(&re), Some(rotated.current_key_id()));
+
+        // بدون کلید قبلی، مقدار قدیمی قابل خواندن نیست
+        let only_new = TokenCipher::new(&new_key, &[]).unwrap();
+        assert!(matches!(only_new.decrypt("42", &stored), Err(CryptoError::UnknownKey(_))));
+    }
+
+    #[test]
+    fn test_bound_to_chat_and_plaintext_passthrough() {
+        let cipher = TokenCipher::new(&generate_key(), &[]).unwrap();
+        let stored = cipher.encrypt("1", "key");
+        assert_eq!(cipher.decrypt("2", &stored), Err(CryptoError::Corrupted));
+        assert_eq!(cipher.decrypt("1", "legacy-plain").unwrap(), "legacy-plain");
+        assert!(parse_key("c2hvcnQ=").is_err());
+    }
+}
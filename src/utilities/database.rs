@@ -0,0 +1,113 @@
+use rusqlite::Connection;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// اتصال مشترک SQLite بین بخش‌های مختلف (اعتبارنامه‌ها، وضعیت مکالمه‌ها)
+pub type SharedConnection = Arc<Mutex<Connection>>;
+
+/// مهاجرت‌های اسکیما به ترتیب؛ شمارهٔ هر نسخه = اندیس + 1 (در `PRAGMA user_version`)
+///
+/// هرگز یک مهاجرت قدیمی را ویرایش نکنید؛ فقط مورد جدید به انتهای لیست اضافه کنید.
+const MIGRATIONS: &[&str] = &[
+    // 1: اطلاعات ورود هر چت (آدرس پنل و Api-Key)
+    "CREATE TABLE user_info (
+        chat_id    TEXT PRIMARY KEY NOT NULL,
+        site       TEXT,
+        token      TEXT,
+        updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+    );",
+    // 2: وضعیت مکالمهٔ هر چت (JSON از `State`) تا با ری‌استارت از دست نرود
+    "CREATE TABLE dialogues (
+        chat_id    INTEGER PRIMARY KEY NOT NULL,
+        state      TEXT NOT NULL,
+        updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+    );",
+    // 3: فیلتر لیست‌های /products؛ دکمه‌های صفحه‌بندی فقط شناسهٔ ردیف را حمل می‌کنند
+    "CREATE TABLE product_filters (
+        id         INTEGER PRIMARY KEY AUTOINCREMENT,
+        chat_id    INTEGER NOT NULL,
+        query      TEXT NOT NULL,
+        created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+    );
+    CREATE INDEX product_filters_chat ON product_filters (chat_id, id);",
+    // 4: سطل بازیافت محصولات حذف‌شده (با بایت تصاویر) و آخرین محصول ساخته‌شدهٔ هر چت
+    "CREATE TABLE trash (
+        chat_id    INTEGER NOT NULL,
+        product_id INTEGER NOT NULL,
+        product    TEXT NOT NULL,
+        deleted_at INTEGER NOT NULL,
+        PRIMARY KEY (chat_id, product_id)
+    );
+    CREATE TABLE trash_images (
+        chat_id    INTEGER NOT NULL,
+        product_id INTEGER NOT NULL,
+        position   INTEGER NOT NULL,
+        filename   TEXT NOT NULL,
+        bytes      BLOB NOT NULL,
+        image_alt  TEXT,
+        is_default INTEGER NOT NULL,
+        PRIMARY KEY (chat_id, product_id, position)
+    );
+    CREATE TABLE last_created (
+        chat_id    INTEGER PRIMARY KEY NOT NULL,
+        product_id INTEGER NOT NULL
+    );",
+    // 5: دسته‌ای که هر چت برای نام هر محصول انتخاب کرده (برای پیشنهاد دسته)
+    "CREATE TABLE category_choices (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        chat_id     INTEGER NOT NULL,
+        words       TEXT NOT NULL,
+        category_id INTEGER NOT NULL,
+        created_at  INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+    );
+    CREATE INDEX category_choices_chat ON category_choices (chat_id, id);",
+];
+
+/// اتصال اصلی برنامه برای داده‌هایی که به backend خاصی وابسته نیستند (فیلتر لیست‌ها، سطل بازیافت، ...)
+static DATABASE: OnceLock<SharedConnection> = OnceLock::new();
+
+/// ثبت اتصال فایل `storage.database_path`؛ فقط یک‌بار در شروع برنامه
+pub fn init_database(conn: SharedConnection) -> &'static SharedConnection {
+    DATABASE.get_or_init(|| conn)
+}
+
+/// اتصال ثبت‌شده؛ اگر فایلی تنظیم نشده باشد داده‌ها در یک پایگاه درون حافظه می‌مانند
+pub fn database() -> &'static SharedConnection {
+    DATABASE.get_or_init(|| open_in_memory().expect("cannot open in-memory database"))
+}
+
+/// باز کردن (یا ساختن) فایل پایگاه داده و اعمال مهاجرت‌های باقی‌مانده
+pub fn open_database<P: AsRef<Path>>(path: P) -> rusqlite::Result<SharedConnection> {
+    if let Some(dir) = path.as_ref().parent()
+        && !dir.as_os_str().is_empty()
+    {
+        // اگر پوشه وجود نداشته باشد، خطای باز کردن فایل از خود SQLite گزارش می‌شود
+        let _ = std::fs::create_dir_all(dir);
+    }
+
+    let mut conn = Connection::open(path)?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    migrate(&mut conn)?;
+    Ok(Arc::new(Mutex::new(conn)))
+}
+
+/// پایگاه دادهٔ درون حافظه (برای تست‌ها)
+pub fn open_in_memory() -> rusqlite::Result<SharedConnection> {
+    let mut conn = Connection::open_in_memory()?;
+    migrate(&mut conn)?;
+    Ok(Arc::new(Mutex::new(conn)))
+}
+
+/// اعمال مهاجرت‌هایی که نسخه‌شان از `user_version` فعلی بزرگ‌تر است
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let current: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
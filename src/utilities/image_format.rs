@@ -0,0 +1,62 @@
+/// ====== تشخیص قالب واقعی تصویر از امضای ابتدای فایل ======
+/// پسوند مسیر تلگرام یا نام فایل کاربر قابل اعتماد نیست؛ قالب از همین بایت‌ها خوانده می‌شود.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Bmp,
+}
+
+impl ImageFormat {
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0xFF, 0xD8, 0xFF, ..] => Some(Self::Jpeg),
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(Self::Png),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(Self::Gif),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(Self::Webp),
+            [b'B', b'M', ..] => Some(Self::Bmp),
+            _ => None,
+        }
+    }
+
+    /// پسوند استاندارد برای نام فایل آپلودی
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+            Self::Bmp => "bmp",
+        }
+    }
+
+    /// آیا در فهرست `images.allowed_extensions` هست (jpg و jpeg یکی حساب می‌شوند)
+    pub fn is_allowed(self, allowed_extensions: &[String]) -> bool {
+        let aliases: &[&str] = match self {
+            Self::Jpeg => &["jpg", "jpeg"],
+            other => &[other.extension()],
+        };
+        allowed_extensions.iter().any(|e| aliases.contains(&e.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod test_image_format {
+    use super::*;
+
+    #[test]
+    fn test_sniff_and_allowed() {
+        assert_eq!(ImageFormat::sniff(&[0xFF, 0xD8, 0xFF, 0xE0, 0]), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::sniff(b"\x89PNG\r\n\x1a\n...."), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some(ImageFormat::Webp));
+        assert_eq!(ImageFormat::sniff(b"GIF89a"), Some(ImageFormat::Gif));
+        assert_eq!(ImageFormat::sniff(b"%PDF-1.7"), None);
+        assert_eq!(ImageFormat::sniff(b"RIFF\0\0\0\0WAVE"), None);
+
+        let allowed = vec!["jpeg".to_string(), "png".to_string()];
+        assert!(ImageFormat::Jpeg.is_allowed(&allowed));
+        assert!(!ImageFormat::Webp.is_allowed(&allowed));
+    }
+}
@@ -1,3 +1,9 @@
 pub mod site;
 pub mod session;
 pub mod token;
+pub mod config;
+pub mod database;
+pub mod credential_store;
+pub mod crypto;
+pub mod text;
+pub mod image_format;
@@ -87,10 +87,6 @@ pub fn current_csrftoken(chat_id: Option<ChatId>) -> Option<String> {
     let cookie_str = cookies.to_str().ok()?;
     cookie_str.split(';').find_map(|p| {
         let p = p.trim();
-        if let Some(v) = p.strip_prefix("csrftoken=") {
-            Some(v.to_string())
-        } else {
-            None
-        }
+        p.strip_prefix("csrftoken=").map(|v| v.to_string())
     })
 }
\ No newline at end of file
@@ -1,64 +1,44 @@
-use std::collections::HashMap;
-use std::sync::{RwLock, OnceLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::utilities::credential_store::credential_store;
 
-///OnceLock: «فقط یک‌بار بسازش، بعداً همان را بده»
-//
-// RwLock: «چندتا خواننده همزمان OK؛ ولی نویسنده باید تنها باشد»
-//
-// HashMap: «دفترچهٔ کلید→مقدار»
-//
-// 'static: «تا آخر برنامه زنده است»
-
-/// TODO :
-/// pub struct user_info {
-///     pub site: String,
-///     pub token: String,
-/// }
-
-/// مدیریت آدرس‌های مرتبط با شناسه‌ها
-pub static SITE: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
-/// فقط یک‌بار لاک را آماده می‌کنیم (داخل تابع)
-fn get_lock() -> &'static RwLock<HashMap<String, String>> {
-    SITE.get_or_init(|| RwLock::new(HashMap::new()))
-}
+// آدرس پنل هر چت در مخزن اعتبارنامه‌ها (`credential_store`) نگهداری می‌شود
+// تا با ری‌استارت ربات از بین نرود.
 
 /// تنظیم مقدار (هر بار قابل تغییر است)
 pub fn set_site<S: Into<String>>(key: S, value: S) {
-    let mut w: RwLockWriteGuard<HashMap<String, String>> =
-        get_lock().write().expect("SITE lock poisoned");
-
-    // Convert the key once to avoid multiple conversions
-    let key_string = key.into();
-
-    if w.contains_key(&key_string) {
-        // Only update if the key exists
-        w.insert(key_string, value.into());
-    }
-    else {
-        w.insert(key_string, value.into());
+    let key: String = key.into();
+    if let Err(e) = credential_store().set_site(&key, &value.into()) {
+        eprintln!("cannot save site of {}: {}", key, e);
     }
 }
 
 /// خواندن یک مقدار با استفاده از کلید
 pub fn get_site<S: AsRef<str>>(key: S) -> Option<String> {
-    let r: RwLockReadGuard<HashMap<String, String>> =
-        get_lock().read().expect("SITE lock poisoned");
-
-    r.get(key.as_ref()).cloned()
+    match credential_store().get(key.as_ref()) {
+        Ok(info) => info.and_then(|i| i.site),
+        Err(e) => {
+            eprintln!("cannot read site of {}: {}", key.as_ref(), e);
+            None
+        }
+    }
 }
 
 /// حذف یک مقدار با کلید
 pub fn remove_site<S: AsRef<str>>(key: S) {
-    let mut w: RwLockWriteGuard<HashMap<String, String>> =
-        get_lock().write().expect("SITE lock poisoned");
-
-    w.remove(key.as_ref());
+    if let Err(e) = credential_store().remove_site(key.as_ref()) {
+        eprintln!("cannot remove site of {}: {}", key.as_ref(), e);
+    }
 }
 
 /// نمایش همه مقادیر ذخیره‌شده
 pub fn list_sites() -> Vec<(String, String)> {
-    let r: RwLockReadGuard<HashMap<String, String>> =
-        get_lock().read().expect("SITE lock poisoned");
-
-    r.iter().map(|(key, value)| (key.clone(), value.clone())).collect()
-}
\ No newline at end of file
+    match credential_store().list() {
+        Ok(all) => all
+            .into_iter()
+            .filter_map(|(key, info)| info.site.map(|site| (key, site)))
+            .collect(),
+        Err(e) => {
+            eprintln!("cannot list sites: {}", e);
+            Vec::new()
+        }
+    }
+}
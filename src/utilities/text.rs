@@ -0,0 +1,64 @@
+/// تبدیل ارقام فارسی (۰-۹) و عربی (٠-٩) به ارقام لاتین
+pub fn normalize_digits(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '۰'..='۹' => char::from(b'0' + (c as u32 - '۰' as u32) as u8),
+            '٠'..='٩' => char::from(b'0' + (c as u32 - '٠' as u32) as u8),
+            _ => c,
+        })
+        .collect()
+}
+
+/// خواندن عدد صحیح از متن کاربر؛ ارقام فارسی و جداکننده‌های هزارگان (`,` `٬` فاصله `_`) مجازند
+pub fn parse_number<T: std::str::FromStr>(s: &str) -> Option<T> {
+    let cleaned: String = normalize_digits(s.trim())
+        .chars()
+        .filter(|c| !matches!(c, ',' | '٬' | '_' | ' ' | '\u{200c}'))
+        .collect();
+    if cleaned.is_empty() {
+        return None;
+    }
+    cleaned.parse::<T>().ok()
+}
+
+/// یکسان‌سازی متن فارسی/عربی برای جستجو: ي/ى→ی، ك→ک، ة→ه، أ/إ/آ→ا، حذف اعراب و کشیده،
+/// نیم‌فاصله به فاصله، حروف کوچک و فاصله‌های تکی
+pub fn normalize_search(s: &str) -> String {
+    normalize_digits(s)
+        .chars()
+        .filter(|c| !matches!(c, '\u{064B}'..='\u{0652}' | 'ـ'))
+        .map(|c| match c {
+            'ي' | 'ى' | 'ئ' => 'ی',
+            'ك' => 'ک',
+            'ة' => 'ه',
+            'أ' | 'إ' | 'آ' => 'ا',
+            'ؤ' => 'و',
+            '\u{200c}' | '-' | '_' | '/' => ' ',
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod test_text {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number::<u64>("۲۵۰٬۰۰۰"), Some(250000));
+        assert_eq!(parse_number::<u32>(" 1,200 "), Some(1200));
+        assert_eq!(parse_number::<u64>("-5"), None);
+        assert_eq!(parse_number::<u64>("abc"), None);
+    }
+
+    #[test]
+    fn test_normalize_search() {
+        assert_eq!(normalize_search("كيف  مردانه"), "کیف مردانه");
+        assert_eq!(normalize_search("گوشی\u{200c}های Samsung"), "گوشی های samsung");
+        assert_eq!(normalize_search("آرايشي"), "ارایشی");
+    }
+}